@@ -0,0 +1,277 @@
+use std::{
+    env,
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// Minimum gap between any two requests we make to the server
+const MIN_INTERVAL: Duration = Duration::from_secs(5);
+const USER_AGENT: &str = "aoc-2023 puzzle runner";
+
+#[derive(Debug)]
+pub enum ClientError {
+    NoSession,
+    Unauthorized,
+    NotUnlocked { year: u16, day: u8 },
+    Status(u16, String),
+    Transport(String),
+    Io(PathBuf, std::io::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::NoSession => write!(
+                f,
+                "no session token, set AOC_SESSION, session_file in aoc.toml or put it in {}",
+                session_file().map_or("~/.config/aoc/session".into(), |x| x.display().to_string())
+            ),
+            ClientError::Unauthorized => {
+                write!(
+                    f,
+                    "the server rejected the session token, it may have expired"
+                )
+            }
+            ClientError::NotUnlocked { year, day } => {
+                write!(f, "day {day} of {year} hasn't unlocked yet")
+            }
+            ClientError::Status(code, body) => {
+                write!(f, "server responded with {code}: {}", body.trim())
+            }
+            ClientError::Transport(e) => write!(f, "request failed: {e}"),
+            ClientError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl Error for ClientError {}
+
+fn session_file() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|x| Path::new(&x).join(".config")))?;
+    Some(config.join("aoc/session"))
+}
+
+/// Session token from AOC_SESSION, falling back to the given file or else
+/// ~/.config/aoc/session
+pub fn find_session(file: Option<&Path>) -> Result<String, ClientError> {
+    let token = match env::var("AOC_SESSION") {
+        Ok(x) => x,
+        Err(_) => file
+            .map(Path::to_owned)
+            .or_else(session_file)
+            .and_then(|x| fs::read_to_string(x).ok())
+            .ok_or(ClientError::NoSession)?,
+    };
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(ClientError::NoSession);
+    }
+    Ok(token.to_string())
+}
+
+// Days from 1970-01-01 to the given civil date (Howard Hinnant's algorithm)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Puzzles unlock at midnight EST, which is 05:00 UTC
+pub fn unlock_time(year: u16, day: u8) -> SystemTime {
+    let seconds = days_from_civil(year as i64, 12, day as i64) * 86400 + 5 * 3600;
+    UNIX_EPOCH + Duration::from_secs(seconds as u64)
+}
+
+/// Whether a day's input is already on disk. new-day leaves empty placeholders
+/// behind, those don't count.
+pub fn is_cached(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|x| x.len() > 0)
+}
+
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    year: u16,
+    /// Directory holding the rate limit stamp and other local state
+    state_dir: PathBuf,
+    min_interval: Duration,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: String, year: u16, state_dir: PathBuf) -> Self {
+        Client {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            year,
+            state_dir,
+            min_interval: MIN_INTERVAL,
+        }
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn state_dir(&self) -> &Path {
+        &self.state_dir
+    }
+
+    fn check_unlocked(&self, day: u8) -> Result<(), ClientError> {
+        if SystemTime::now() < unlock_time(self.year, day) {
+            return Err(ClientError::NotUnlocked {
+                year: self.year,
+                day,
+            });
+        }
+        Ok(())
+    }
+
+    // The stamp lives on disk so back-to-back runs of the binary are throttled too
+    fn wait_for_slot(&self) -> Result<(), ClientError> {
+        let stamp = self.state_dir.join("last_request");
+        let last = fs::metadata(&stamp).and_then(|x| x.modified()).ok();
+        if let Some(elapsed) = last.and_then(|x| x.elapsed().ok()) {
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+        fs::create_dir_all(&self.state_dir)
+            .and_then(|_| fs::write(&stamp, ""))
+            .map_err(|e| ClientError::Io(stamp, e))
+    }
+
+    fn call(
+        &self,
+        request: ureq::Request,
+        form: Option<&[(&str, &str)]>,
+    ) -> Result<String, ClientError> {
+        self.wait_for_slot()?;
+        let request = request.set("Cookie", &format!("session={}", self.session));
+        let response = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+        match response {
+            Ok(x) => x
+                .into_string()
+                .map_err(|e| ClientError::Transport(e.to_string())),
+            // the server answers 400 for a missing or bad session cookie
+            Err(ureq::Error::Status(400 | 401 | 403, _)) => Err(ClientError::Unauthorized),
+            Err(ureq::Error::Status(code, x)) => Err(ClientError::Status(
+                code,
+                x.into_string().unwrap_or_default(),
+            )),
+            Err(e) => Err(ClientError::Transport(e.to_string())),
+        }
+    }
+
+    pub fn get(&self, day: u8, path: &str) -> Result<String, ClientError> {
+        self.check_unlocked(day)?;
+        let url = format!("{}/{}/day/{day}{path}", self.base_url, self.year);
+        self.call(self.agent.get(&url), None).map_err(|e| match e {
+            ClientError::Status(404, _) => ClientError::NotUnlocked {
+                year: self.year,
+                day,
+            },
+            e => e,
+        })
+    }
+
+    pub fn post(&self, day: u8, path: &str, form: &[(&str, &str)]) -> Result<String, ClientError> {
+        self.check_unlocked(day)?;
+        let url = format!("{}/{}/day/{day}{path}", self.base_url, self.year);
+        self.call(self.agent.post(&url), Some(form))
+    }
+
+    /// Download the input for a day into `path`, replacing whatever is there
+    pub fn fetch_input(&self, day: u8, path: &Path) -> Result<(), ClientError> {
+        let input = self.get(day, "/input")?;
+        fs::write(path, input).map_err(|e| ClientError::Io(path.to_owned(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{scratch_dir, serve};
+    use std::time::Instant;
+
+    fn client(base_url: &str, state_dir: PathBuf) -> Client {
+        Client {
+            min_interval: Duration::ZERO,
+            ..Client::new(base_url, "token".to_string(), 2023, state_dir)
+        }
+    }
+
+    #[test]
+    fn fetch_saves_the_input() {
+        let (base_url, requests) = serve(vec![(200, "1abc2\n")]);
+        let dir = scratch_dir("fetch");
+        let path = dir.join("day1_input.txt");
+        client(&base_url, dir).fetch_input(1, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1abc2\n");
+        assert!(is_cached(&path));
+        let head = requests.recv().unwrap();
+        assert!(head.starts_with("GET /2023/day/1/input "), "{head}");
+        assert!(head.contains("session=token"), "{head}");
+    }
+
+    #[test]
+    fn rejected_session_is_unauthorized() {
+        let (base_url, _requests) = serve(vec![(400, ""), (401, ""), (403, "")]);
+        let client = client(&base_url, scratch_dir("unauthorized"));
+        for _ in 0..3 {
+            let e = client.get(1, "/input").unwrap_err();
+            assert!(matches!(e, ClientError::Unauthorized), "{e}");
+        }
+    }
+
+    #[test]
+    fn missing_day_is_not_unlocked() {
+        let (base_url, _requests) = serve(vec![(404, "Not Found"), (500, "oops")]);
+        let client = client(&base_url, scratch_dir("missing"));
+        let e = client.get(3, "/input").unwrap_err();
+        assert!(
+            matches!(e, ClientError::NotUnlocked { year: 2023, day: 3 }),
+            "{e}"
+        );
+        let e = client.get(3, "/input").unwrap_err();
+        assert!(
+            matches!(e, ClientError::Status(500, ref body) if body == "oops"),
+            "{e}"
+        );
+    }
+
+    #[test]
+    fn recent_stamp_delays_the_next_request() {
+        let (base_url, _requests) = serve(vec![(200, "a"), (200, "b")]);
+        let dir = scratch_dir("stamp");
+        let client = Client {
+            min_interval: Duration::from_millis(300),
+            ..client(&base_url, dir.clone())
+        };
+        // as if another run had just made a request
+        fs::write(dir.join("last_request"), "").unwrap();
+        let start = Instant::now();
+        client.get(1, "/input").unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(250));
+
+        let start = Instant::now();
+        client.get(1, "/input").unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,205 @@
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub const FILE_NAME: &str = "aoc.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+// aoc.toml as written; every key is optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    year: Option<u16>,
+    input_dir: Option<PathBuf>,
+    input_pattern: Option<String>,
+    example_pattern: Option<String>,
+    output: Option<OutputFormat>,
+    timeouts: HashMap<String, f64>,
+    session_file: Option<PathBuf>,
+    base_url: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Invalid(PathBuf, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            ConfigError::Parse(path, e) => write!(f, "{}: {e}", path.display()),
+            ConfigError::Invalid(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Runner settings, from the nearest aoc.toml or the defaults when there isn't one
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Directory relative paths in the file are resolved against
+    pub root: PathBuf,
+    pub year: u16,
+    pub input_dir: PathBuf,
+    /// File names under input_dir, with {day}, {part} and {n} filled in
+    pub input_pattern: String,
+    /// {n} is the example number and is left out for the first example
+    pub example_pattern: String,
+    pub output: OutputFormat,
+    /// Keyed by day and optionally part; (0, None) is the fallback for everything
+    timeouts: HashMap<(u8, Option<u8>), Duration>,
+    pub session_file: Option<PathBuf>,
+    pub base_url: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            root: PathBuf::new(),
+            year: 2023,
+            input_dir: PathBuf::from("examples"),
+            input_pattern: "day{day}_input.txt".to_string(),
+            example_pattern: "day{day}_example{n}.txt".to_string(),
+            output: OutputFormat::Text,
+            timeouts: HashMap::new(),
+            session_file: None,
+            base_url: None,
+        }
+    }
+}
+
+fn fill(pattern: &str, day: u8, part: u8, n: u8) -> String {
+    pattern
+        .replace("{day}", &day.to_string())
+        .replace("{part}", &part.to_string())
+        .replace("{n}", &if n == 1 { String::new() } else { n.to_string() })
+}
+
+fn check_pattern(pattern: &str) -> Result<(), String> {
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or(format!("unclosed placeholder in {pattern:?}"))?;
+        let name = &rest[start + 1..start + end];
+        if !["day", "part", "n"].contains(&name) {
+            return Err(format!(
+                "unknown placeholder {{{name}}} in {pattern:?}, expected {{day}}, {{part}} or {{n}}"
+            ));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    if !pattern.contains("{day}") {
+        return Err(format!("{pattern:?} needs a {{day}} placeholder"));
+    }
+    Ok(())
+}
+
+// "default", "dayN" or "dayN_partP"
+fn parse_timeout_key(key: &str) -> Option<(u8, Option<u8>)> {
+    if key == "default" {
+        return Some((0, None));
+    }
+    let rest = key.strip_prefix("day")?;
+    let (day, part) = match rest.split_once("_part") {
+        Some((day, part)) => (day, Some(part.parse().ok()?)),
+        None => (rest, None),
+    };
+    Some((day.parse().ok().filter(|x| (1..=25).contains(x))?, part))
+}
+
+fn expand_home(path: PathBuf) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => path,
+    }
+}
+
+impl Config {
+    /// Look for aoc.toml in the current directory and each parent in turn
+    pub fn find() -> Result<Config, ConfigError> {
+        let cwd = env::current_dir().map_err(|e| ConfigError::Io(".".into(), e))?;
+        match cwd
+            .ancestors()
+            .map(|x| x.join(FILE_NAME))
+            .find(|x| x.is_file())
+        {
+            Some(path) => Config::load(&path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_owned(), e))?;
+        let raw: RawConfig =
+            toml::from_str(&text).map_err(|e| ConfigError::Parse(path.to_owned(), e))?;
+        let invalid = |e: String| ConfigError::Invalid(path.to_owned(), e);
+
+        let root = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let defaults = Config::default();
+        let mut timeouts = HashMap::new();
+        for (key, seconds) in raw.timeouts {
+            let slot = parse_timeout_key(&key).ok_or_else(|| {
+                invalid(format!(
+                    "invalid key timeouts.{key}, expected default, dayN or dayN_partP"
+                ))
+            })?;
+            let timeout = Duration::try_from_secs_f64(seconds)
+                .map_err(|e| invalid(format!("timeouts.{key}: {e}")))?;
+            timeouts.insert(slot, timeout);
+        }
+        let config = Config {
+            year: raw.year.unwrap_or(defaults.year),
+            input_dir: root.join(raw.input_dir.unwrap_or(defaults.input_dir)),
+            input_pattern: raw.input_pattern.unwrap_or(defaults.input_pattern),
+            example_pattern: raw.example_pattern.unwrap_or(defaults.example_pattern),
+            output: raw.output.unwrap_or(defaults.output),
+            timeouts,
+            session_file: raw.session_file.map(|x| root.join(expand_home(x))),
+            base_url: raw.base_url,
+            root,
+        };
+        check_pattern(&config.input_pattern).map_err(|e| invalid(format!("input_pattern: {e}")))?;
+        check_pattern(&config.example_pattern)
+            .map_err(|e| invalid(format!("example_pattern: {e}")))?;
+        Ok(config)
+    }
+
+    pub fn input_path(&self, day: u8, part: u8) -> PathBuf {
+        self.input_dir.join(fill(&self.input_pattern, day, part, 1))
+    }
+
+    pub fn example_path(&self, day: u8, part: u8, n: u8) -> PathBuf {
+        self.input_dir
+            .join(fill(&self.example_pattern, day, part, n))
+    }
+
+    /// Most specific configured time budget for a solution
+    pub fn timeout(&self, day: u8, part: u8) -> Option<Duration> {
+        [(day, Some(part)), (day, None), (0, None)]
+            .iter()
+            .find_map(|x| self.timeouts.get(x).copied())
+    }
+
+    /// Where fetch and submit keep their rate limit stamp and answer log
+    pub fn state_dir(&self) -> PathBuf {
+        self.root.join(".aoc")
+    }
+}
@@ -1,21 +1,27 @@
+use aoc_core::SolveResult;
+use log::debug;
 use std::collections::HashMap;
 
-pub fn day_1_p1() {
-    let input_strings: Vec<&str> = include_str!("../examples/day1_input.txt").lines().collect();
+pub fn day_1_p1(input: &str) -> SolveResult {
+    let input_strings: Vec<&str> = input.lines().collect();
     let digits: Vec<u32> = input_strings
         .iter()
-        .map(|l| l.chars().filter(|x| x.is_digit(10)).collect::<Vec<char>>())
+        .map(|l| {
+            l.chars()
+                .filter(|x| x.is_ascii_digit())
+                .collect::<Vec<char>>()
+        })
         .map(|x| {
             (x.first().unwrap().to_string() + &x.last().unwrap().to_string())
                 .parse::<u32>()
                 .unwrap()
         })
         .collect();
-    println!("digit list: {:#?}", digits);
-    println!("digits sum: {:#?}", digits.iter().sum::<u32>());
+    debug!("digit list: {:?}", digits);
+    Ok(digits.iter().sum::<u32>().to_string())
 }
 
-pub fn day_1_p2() {
+pub fn day_1_p2(input: &str) -> SolveResult {
     let numeric_map: HashMap<&str, &str> = HashMap::from_iter(vec![
         ("one", "1"),
         ("two", "2"),
@@ -28,18 +34,18 @@ pub fn day_1_p2() {
         ("nine", "9"),
     ]);
 
-    let input_strings: Vec<&str> = include_str!("../examples/day1_input.txt").lines().collect();
+    let input_strings: Vec<&str> = input.lines().collect();
     let mut output_numbers: Vec<u32> = Vec::new();
 
     for &line in &input_strings {
         let mut leftmost: String = "".to_string();
         let mut rightmost: String = "".to_string();
         for i in 0..line.len() {
-            if leftmost != "" {
+            if !leftmost.is_empty() {
                 break;
             }
             let cur_char = line.chars().collect::<Vec<char>>()[i];
-            if cur_char.is_digit(10) {
+            if cur_char.is_ascii_digit() {
                 leftmost = cur_char.to_string();
             }
             for &numtext in numeric_map.keys() {
@@ -50,11 +56,11 @@ pub fn day_1_p2() {
         }
 
         for i in 1..=line.len() {
-            if rightmost != "" {
+            if !rightmost.is_empty() {
                 break;
             }
             let cur_char = line.chars().collect::<Vec<char>>()[line.len() - i];
-            if cur_char.is_digit(10) {
+            if cur_char.is_ascii_digit() {
                 rightmost = cur_char.to_string();
             }
             for &numtext in numeric_map.keys() {
@@ -65,6 +71,6 @@ pub fn day_1_p2() {
         }
         output_numbers.push((leftmost + &rightmost).parse::<u32>().unwrap());
     }
-    println!("{:#?}", output_numbers);
-    println!("{}", output_numbers.iter().sum::<u32>());
+    debug!("numbers: {:?}", output_numbers);
+    Ok(output_numbers.iter().sum::<u32>().to_string())
 }
@@ -1,3 +1,6 @@
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
+
 #[derive(Debug)]
 pub struct ColourCounts {
     pub red: u32,
@@ -5,77 +8,49 @@ pub struct ColourCounts {
     pub blue: u32,
 }
 
-pub fn day_2_p1() {
-    let input_strings: Vec<&str> = include_str!("../examples/day2_input.txt").lines().collect();
-    let mut possible_games: Vec<usize> = Vec::new();
-
-    for (idx, game) in input_strings.iter().enumerate() {
+// largest count of each colour seen across all rounds of each game
+pub(crate) fn get_day2_input(input: &str) -> Result<Vec<ColourCounts>, ParseError> {
+    parse::lines(input, |game| {
         let mut colour_count = ColourCounts {
             red: 0,
             green: 0,
             blue: 0,
         };
 
-        let rounds: Vec<&str> = game.split(": ").last().unwrap().split("; ").collect();
+        let (_, rounds) = parse::key_value(game, ":")?;
 
-        for round in rounds {
+        for round in rounds.split("; ") {
             for count in round.split(", ") {
-                let per_c_counts = count.split(" ").collect::<Vec<&str>>();
-                match per_c_counts[..] {
-                    [x, "red"] => {
-                        colour_count.red = colour_count.red.max(x.parse::<u32>().unwrap())
-                    }
-                    [x, "green"] => {
-                        colour_count.green = colour_count.green.max(x.parse::<u32>().unwrap())
-                    }
-                    [x, "blue"] => {
-                        colour_count.blue = colour_count.blue.max(x.parse::<u32>().unwrap())
-                    }
-                    _ => panic!(),
+                let (x, colour) = parse::split_pair(count, " ")?;
+                let x: u32 = parse::value(x)?;
+                match colour {
+                    "red" => colour_count.red = colour_count.red.max(x),
+                    "green" => colour_count.green = colour_count.green.max(x),
+                    "blue" => colour_count.blue = colour_count.blue.max(x),
+                    _ => return Err(ParseError::invalid(colour, "red, green or blue")),
                 }
             }
         }
+        Ok(colour_count)
+    })
+}
+
+pub fn day_2_p1(games: Vec<ColourCounts>) -> SolveResult {
+    let mut possible_games: Vec<usize> = Vec::new();
+
+    for (idx, colour_count) in games.iter().enumerate() {
         if colour_count.red <= 12 && colour_count.green <= 13 && colour_count.blue <= 14 {
             possible_games.push(idx + 1)
         }
     }
-    println!(
-        "sum of possible={:#?}",
-        possible_games.iter().sum::<usize>()
-    );
+    Ok(possible_games.iter().sum::<usize>().to_string())
 }
 
-pub fn day_2_p2() {
-    let input_strings: Vec<&str> = include_str!("../examples/day2_input.txt").lines().collect();
+pub fn day_2_p2(games: Vec<ColourCounts>) -> SolveResult {
     let mut game_powers: Vec<u32> = Vec::new();
 
-    for game in input_strings {
-        let mut colour_count = ColourCounts {
-            red: 0,
-            green: 0,
-            blue: 0,
-        };
-
-        let rounds: Vec<&str> = game.split(": ").last().unwrap().split("; ").collect();
-
-        for round in rounds {
-            for count in round.split(", ") {
-                let per_c_counts = count.split(" ").collect::<Vec<&str>>();
-                match per_c_counts[..] {
-                    [x, "red"] => {
-                        colour_count.red = colour_count.red.max(x.parse::<u32>().unwrap())
-                    }
-                    [x, "green"] => {
-                        colour_count.green = colour_count.green.max(x.parse::<u32>().unwrap())
-                    }
-                    [x, "blue"] => {
-                        colour_count.blue = colour_count.blue.max(x.parse::<u32>().unwrap())
-                    }
-                    _ => panic!(),
-                }
-            }
-        }
+    for colour_count in games {
         game_powers.push(colour_count.red * colour_count.green * colour_count.blue)
     }
-    println!("sum of powers={:#?}", game_powers.iter().sum::<u32>());
+    Ok(game_powers.iter().sum::<u32>().to_string())
 }
@@ -1,49 +1,54 @@
+use crate::grid::Grid;
+use crate::parse::{self, ParseError};
+use crate::point::Point;
+use aoc_core::SolveResult;
+use std::collections::HashSet;
+
 #[derive(Debug)]
 pub struct NumericString {
     pub number: u32,
-    pub row: u32,
-    pub col_start: u32,
-    pub length: u32,
+    pub start: Point,
+    pub length: usize,
     pub enabled: bool,
 }
 
-#[derive(Debug)]
-pub struct SymbolLocation(pub u32, pub u32);
-
 #[derive(Debug)]
 pub struct GearDetails {
-    pub row: u32,
-    pub col: u32,
+    pub position: Point,
     pub adjacent_numbers: Vec<u32>,
 }
 
-pub fn day3_p1() {
-    let input_string = include_str!("../examples/day3_input.txt");
+impl NumericString {
+    /// Every cell touching one of the digits, diagonals included
+    fn adjacent_cells(&self, grid: &Grid<char>) -> HashSet<Point> {
+        (self.start.col..self.start.col + self.length)
+            .flat_map(|col| grid.neighbours8(Point::new(self.start.row, col)))
+            .collect()
+    }
+}
 
-    // first collect all numbers and their positions
+pub(crate) fn get_day3_input(input: &str) -> Result<Grid<char>, ParseError> {
+    parse::grid(input)
+}
+
+// collect all numbers and their positions, running along each row
+fn find_numbers(grid: &Grid<char>) -> Vec<NumericString> {
     let mut numeric_strings: Vec<NumericString> = Vec::new();
-    // plus all symbol positions
-    let mut symbol_positions: Vec<SymbolLocation> = Vec::new();
-    // iterate over rows/lines, track row idx
-    for (r_idx, r) in input_string.lines().enumerate() {
-        // iterate over chars in line
+    for (r_idx, r) in grid.rows().enumerate() {
         let mut cur_numeric: Vec<char> = Vec::new();
-        for (c_idx, c) in r.chars().enumerate() {
-            if !(c.is_digit(10) || c == '.') {
-                symbol_positions.push(SymbolLocation(r_idx as u32, c_idx as u32));
-            } else if c.is_digit(10) {
+        // chain on a non-digit so numbers at the end of the line get flushed too
+        for (c_idx, &c) in r.iter().chain(&['.']).enumerate() {
+            if c.is_ascii_digit() {
                 cur_numeric.push(c);
-            }
-            if !cur_numeric.is_empty() && (!c.is_digit(10) || c_idx == (r.len() - 1)) {
-                let len = cur_numeric.len() as u32;
+            } else if !cur_numeric.is_empty() {
+                let len = cur_numeric.len();
                 numeric_strings.push(NumericString {
                     number: cur_numeric
                         .iter()
                         .collect::<String>()
                         .parse::<u32>()
                         .unwrap(),
-                    row: r_idx as u32,
-                    col_start: c_idx as u32 - len,
+                    start: Point::new(r_idx, c_idx - len),
                     length: len,
                     enabled: false,
                 });
@@ -51,80 +56,43 @@ pub fn day3_p1() {
             }
         }
     }
-    for sl in &symbol_positions {
-        for num in &mut numeric_strings {
-            if num.enabled {
-                continue;
-            }
-            if num.row == sl.0 - 1 || num.row == sl.0 || num.row == sl.0 + 1 {
-                // if row adjacent, runs within a diagonal
-                for offset in 0..num.length {
-                    let position = num.col_start + offset;
-                    if position == sl.1 - 1 || position == sl.1 || position == sl.1 + 1 {
-                        num.enabled = true;
-                        break;
-                    }
-                }
-            }
-        }
+    numeric_strings
+}
+
+pub fn day3_p1(grid: Grid<char>) -> SolveResult {
+    let mut numeric_strings = find_numbers(&grid);
+
+    for num in &mut numeric_strings {
+        // enabled if any neighbour is a symbol
+        num.enabled = num
+            .adjacent_cells(&grid)
+            .iter()
+            .any(|x| !(grid[*x].is_ascii_digit() || grid[*x] == '.'));
     }
     let adjacent_sum = numeric_strings
         .iter()
         .filter(|x| x.enabled)
         .map(|x| x.number)
         .sum::<u32>();
-    println!("Adjacent values sum: {:#?}", adjacent_sum);
+    Ok(adjacent_sum.to_string())
 }
 
-pub fn day3_p2() {
-    let input_string = include_str!("../examples/day3_input.txt");
+pub fn day3_p2(grid: Grid<char>) -> SolveResult {
+    let numeric_strings = find_numbers(&grid);
 
-    // first collect all numbers and their positions
-    let mut numeric_strings: Vec<NumericString> = Vec::new();
-    // plus all gears
-    let mut gears: Vec<GearDetails> = Vec::new();
-    // iterate over rows/lines, track row idx
-    for (r_idx, r) in input_string.lines().enumerate() {
-        // iterate over chars in line
-        let mut cur_numeric: Vec<char> = Vec::new();
-        for (c_idx, c) in r.chars().enumerate() {
-            if c == '*' {
-                gears.push(GearDetails {
-                    row: r_idx as u32,
-                    col: c_idx as u32,
-                    adjacent_numbers: Vec::new(),
-                });
-            } else if c.is_digit(10) {
-                cur_numeric.push(c);
-            }
-            if !cur_numeric.is_empty() && (!c.is_digit(10) || c_idx == (r.len() - 1)) {
-                let len = cur_numeric.len() as u32;
-                numeric_strings.push(NumericString {
-                    number: cur_numeric
-                        .iter()
-                        .collect::<String>()
-                        .parse::<u32>()
-                        .unwrap(),
-                    row: r_idx as u32,
-                    col_start: c_idx as u32 - len,
-                    length: len,
-                    enabled: false,
-                });
-                cur_numeric = Vec::new();
-            }
-        }
-    }
-    for g in &mut gears {
-        for num in &mut numeric_strings {
-            if num.row == g.row - 1 || num.row == g.row || num.row == g.row + 1 {
-                // if row adjacent, runs within a diagonal
-                for offset in 0..num.length {
-                    let position = num.col_start + offset;
-                    if position == g.col - 1 || position == g.col || position == g.col + 1 {
-                        g.adjacent_numbers.push(num.number);
-                        break;
-                    }
-                }
+    let mut gears: Vec<GearDetails> = grid
+        .iter()
+        .filter(|(_, &c)| c == '*')
+        .map(|(position, _)| GearDetails {
+            position,
+            adjacent_numbers: Vec::new(),
+        })
+        .collect();
+    for num in &numeric_strings {
+        let adjacent = num.adjacent_cells(&grid);
+        for g in &mut gears {
+            if adjacent.contains(&g.position) {
+                g.adjacent_numbers.push(num.number);
             }
         }
     }
@@ -134,5 +102,5 @@ pub fn day3_p2() {
         .map(|x| x.adjacent_numbers.iter().product::<u32>())
         .sum();
 
-    println!("Gear ratio sum: {:#?}", ratio_sum);
+    Ok(ratio_sum.to_string())
 }
@@ -1,25 +1,24 @@
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
 use std::collections::{HashMap, HashSet};
 
-pub fn day4_p1() {
-    let input_str = include_str!("../examples/day4_input.txt");
+// (winning numbers, numbers we have)
+type Card = (HashSet<u32>, HashSet<u32>);
+
+pub(crate) fn get_day4_input(input: &str) -> Result<Vec<Card>, ParseError> {
+    parse::lines(input, |card| {
+        let (_, numbers) = parse::key_value(card, ":")?;
+        let (win, ours) = parse::split_pair(numbers, " | ")?;
+        Ok((
+            parse::ints(win)?.into_iter().collect(),
+            parse::ints(ours)?.into_iter().collect(),
+        ))
+    })
+}
+
+pub(crate) fn day4_p1(cards: Vec<Card>) -> SolveResult {
     let mut winning_numbers: Vec<Vec<u32>> = Vec::new();
-    for card in input_str.lines() {
-        let win_set: HashSet<u32>;
-        let our_set: HashSet<u32>;
-        let outputs: Vec<&str> = card.split(": ").last().unwrap().split(" | ").collect();
-        match outputs.as_slice() {
-            [win, ours] => {
-                win_set = HashSet::from_iter(
-                    win.split_ascii_whitespace()
-                        .map(|x| x.parse::<u32>().unwrap()),
-                );
-                our_set = HashSet::from_iter(
-                    ours.split_ascii_whitespace()
-                        .map(|x| x.parse::<u32>().unwrap()),
-                );
-            }
-            _ => panic!("unexpected split length"),
-        }
+    for (win_set, our_set) in cards {
         winning_numbers.push(
             win_set
                 .intersection(&our_set)
@@ -37,30 +36,13 @@ pub fn day4_p1() {
             }
         })
         .sum::<u32>();
-    println!("{factorial_sum:#?}");
+    Ok(factorial_sum.to_string())
 }
 
-pub fn day4_p2() {
-    let input_str = include_str!("../examples/day4_input.txt");
+pub(crate) fn day4_p2(cards: Vec<Card>) -> SolveResult {
     // collect sets of winning values same as before
     let mut winning_numbers: Vec<Vec<u32>> = Vec::new();
-    for card in input_str.lines() {
-        let win_set: HashSet<u32>;
-        let our_set: HashSet<u32>;
-        let outputs: Vec<&str> = card.split(": ").last().unwrap().split(" | ").collect();
-        match outputs.as_slice() {
-            [win, ours] => {
-                win_set = HashSet::from_iter(
-                    win.split_ascii_whitespace()
-                        .map(|x| x.parse::<u32>().unwrap()),
-                );
-                our_set = HashSet::from_iter(
-                    ours.split_ascii_whitespace()
-                        .map(|x| x.parse::<u32>().unwrap()),
-                );
-            }
-            _ => panic!("unexpected split length"),
-        }
+    for (win_set, our_set) in cards {
         winning_numbers.push(
             win_set
                 .intersection(&our_set)
@@ -83,9 +65,9 @@ pub fn day4_p2() {
     // start from card 1 and iteratively add the future cards
     for i in 1..=winning_numbers.len() as u32 {
         // find how many wins we had
-        let wins = win_hashmap.get(&i).unwrap().clone();
+        let wins = *win_hashmap.get(&i).unwrap();
         // find how many of the current card we had to multiply effect
-        let num_card = cards_hashmap.get(&i).unwrap().clone();
+        let num_card = *cards_hashmap.get(&i).unwrap();
 
         if wins == 0 {
             continue;
@@ -98,5 +80,5 @@ pub fn day4_p2() {
         }
     }
 
-    println!("Total cards: {}", cards_hashmap.values().sum::<u32>());
+    Ok(cards_hashmap.values().sum::<u32>().to_string())
 }
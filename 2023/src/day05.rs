@@ -1,4 +1,7 @@
-use std::thread;
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
+use log::debug;
+use std::ops::Range;
 
 #[derive(Debug, Clone)]
 pub struct SeedMapping {
@@ -16,13 +19,13 @@ impl SeedMapping {
         if self.contains_seed(seed_number) {
             return self.dest_start + (seed_number - self.src_start);
         }
-        seed_number.clone()
+        *seed_number
     }
 }
 
 type SeedMapVec = Vec<SeedMapping>;
 
-trait SeedMapSequence {
+pub(crate) trait SeedMapSequence {
     fn map_sequential(&self, seed_number: &u64) -> u64;
 }
 impl SeedMapSequence for SeedMapVec {
@@ -32,174 +35,112 @@ impl SeedMapSequence for SeedMapVec {
                 return i.map_seed(seed_number);
             }
         }
-        seed_number.clone()
+        *seed_number
     }
 }
 
-pub fn read_day5_input() -> (
-    Vec<u64>,
-    Vec<SeedMapping>,
-    Vec<SeedMapping>,
-    Vec<SeedMapping>,
-    Vec<SeedMapping>,
-    Vec<SeedMapping>,
-    Vec<SeedMapping>,
-    Vec<SeedMapping>,
-) {
-    let input_str = include_str!("../examples/day5_input.txt");
-    let split_lines: Vec<&str> = input_str.split("\n").filter(|x| x != &"").collect();
+// one line of a map: "<dest start> <src start> <length>"
+fn parse_mapping(line: &str) -> Result<SeedMapping, ParseError> {
+    let [dest_start, src_start, length] = parse::array(parse::ints(line)?)?;
+    Ok(SeedMapping {
+        dest_start,
+        src_start,
+        length,
+    })
+}
 
-    let seed_numbers: Vec<u64> = split_lines[0]
-        .split(" ")
-        .skip(1)
-        .map(|x| x.parse::<u64>().unwrap())
-        .collect();
+/// Names of the almanac's maps, in the order seeds go through them
+pub(crate) const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+// seeds, then each map in order from seed-to-soil through to humidity-to-location
+type Almanac = (
+    Vec<u64>,
+    SeedMapVec,
+    SeedMapVec,
+    SeedMapVec,
+    SeedMapVec,
+    SeedMapVec,
+    SeedMapVec,
+    SeedMapVec,
+);
+
+pub fn read_day5_input(input: &str) -> Result<Almanac, ParseError> {
+    let sections = parse::sections(input)?;
+    let maps = |label: &str| parse::lines(sections.get(label)?, parse_mapping);
+
+    Ok((
+        parse::ints(sections.get("seeds")?)?,
+        maps("seed-to-soil map")?,
+        maps("soil-to-fertilizer map")?,
+        maps("fertilizer-to-water map")?,
+        maps("water-to-light map")?,
+        maps("light-to-temperature map")?,
+        maps("temperature-to-humidity map")?,
+        maps("humidity-to-location map")?,
+    ))
+}
 
-    let seed_to_soil_pos = split_lines
-        .iter()
-        .position(|x| x == &"seed-to-soil map:")
-        .unwrap();
-    let soil_to_fertilizer_pos = split_lines
+/// Lowest location for any seed in the ranges, mapping each seed on its own
+pub(crate) fn lowest_location_per_seed(
+    seeds: &[Range<u64>],
+    stages: &[&SeedMapVec],
+) -> Option<u64> {
+    seeds
         .iter()
-        .position(|x| x == &"soil-to-fertilizer map:")
-        .unwrap();
-    let fertilizer_to_water_pos = split_lines
-        .iter()
-        .position(|x| x == &"fertilizer-to-water map:")
-        .unwrap();
-    let water_to_light_pos = split_lines
-        .iter()
-        .position(|x| x == &"water-to-light map:")
-        .unwrap();
-    let light_to_temperature_pos = split_lines
-        .iter()
-        .position(|x| x == &"light-to-temperature map:")
-        .unwrap();
-    let temperature_to_humidity_pos = split_lines
-        .iter()
-        .position(|x| x == &"temperature-to-humidity map:")
-        .unwrap();
-    let humidity_to_location_pos = split_lines
-        .iter()
-        .position(|x| x == &"humidity-to-location map:")
-        .unwrap();
+        .flat_map(|x| x.clone())
+        .map(|seed| stages.iter().fold(seed, |x, maps| maps.map_sequential(&x)))
+        .min()
+}
 
-    let seed_soil_maps: SeedMapVec = split_lines[seed_to_soil_pos + 1..soil_to_fertilizer_pos]
-        .iter()
-        .map(|x| {
-            let elements: Vec<u64> = x.split(" ").map(|i| i.parse::<u64>().unwrap()).collect();
-            match elements[..] {
-                [dest_start, src_start, length] => SeedMapping {
-                    dest_start,
-                    src_start,
-                    length,
-                },
-                _ => panic!("Found non-3 length mapping"),
-            }
-        })
-        .collect();
-    let soil_fertilizer_maps: SeedMapVec = split_lines
-        [soil_to_fertilizer_pos + 1..fertilizer_to_water_pos]
-        .iter()
-        .map(|x| {
-            let elements: Vec<u64> = x.split(" ").map(|i| i.parse::<u64>().unwrap()).collect();
-            match elements[..] {
-                [dest_start, src_start, length] => SeedMapping {
-                    dest_start,
-                    src_start,
-                    length,
-                },
-                _ => panic!("Found non-3 length mapping"),
-            }
-        })
-        .collect();
-    let fertilizer_water_maps: SeedMapVec = split_lines
-        [fertilizer_to_water_pos + 1..water_to_light_pos]
-        .iter()
-        .map(|x| {
-            let elements: Vec<u64> = x.split(" ").map(|i| i.parse::<u64>().unwrap()).collect();
-            match elements[..] {
-                [dest_start, src_start, length] => SeedMapping {
-                    dest_start,
-                    src_start,
-                    length,
-                },
-                _ => panic!("Found non-3 length mapping"),
-            }
-        })
-        .collect();
-    let water_light_maps: SeedMapVec = split_lines
-        [water_to_light_pos + 1..light_to_temperature_pos]
-        .iter()
-        .map(|x| {
-            let elements: Vec<u64> = x.split(" ").map(|i| i.parse::<u64>().unwrap()).collect();
-            match elements[..] {
-                [dest_start, src_start, length] => SeedMapping {
-                    dest_start,
-                    src_start,
-                    length,
-                },
-                _ => panic!("Found non-3 length mapping"),
-            }
-        })
-        .collect();
-    let light_temperature_maps: SeedMapVec = split_lines
-        [light_to_temperature_pos + 1..temperature_to_humidity_pos]
-        .iter()
-        .map(|x| {
-            let elements: Vec<u64> = x.split(" ").map(|i| i.parse::<u64>().unwrap()).collect();
-            match elements[..] {
-                [dest_start, src_start, length] => SeedMapping {
-                    dest_start,
-                    src_start,
-                    length,
-                },
-                _ => panic!("Found non-3 length mapping"),
+// Push whole ranges through one map, splitting them wherever a mapping starts or ends.
+// The first mapping touching a range wins, same as map_sequential.
+fn map_ranges(maps: &SeedMapVec, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    let mut mapped = Vec::new();
+    let mut pending = ranges;
+    while let Some(range) = pending.pop() {
+        if range.is_empty() {
+            continue;
+        }
+        let overlap = maps.iter().find_map(|x| {
+            let start = range.start.max(x.src_start);
+            let end = range.end.min(x.src_start + x.length);
+            (start < end).then_some((x, start, end))
+        });
+        match overlap {
+            Some((mapping, start, end)) => {
+                mapped.push(mapping.map_seed(&start)..mapping.map_seed(&(end - 1)) + 1);
+                pending.push(range.start..start);
+                pending.push(end..range.end);
             }
-        })
-        .collect();
-    let temperature_humidity_maps: SeedMapVec = split_lines
-        [temperature_to_humidity_pos + 1..humidity_to_location_pos]
+            None => mapped.push(range),
+        }
+    }
+    mapped
+}
+
+/// Lowest location for any seed in the ranges, mapping whole ranges at a time
+pub(crate) fn lowest_location_by_ranges(
+    seeds: &[Range<u64>],
+    stages: &[&SeedMapVec],
+) -> Option<u64> {
+    stages
         .iter()
-        .map(|x| {
-            let elements: Vec<u64> = x.split(" ").map(|i| i.parse::<u64>().unwrap()).collect();
-            match elements[..] {
-                [dest_start, src_start, length] => SeedMapping {
-                    dest_start,
-                    src_start,
-                    length,
-                },
-                _ => panic!("Found non-3 length mapping"),
-            }
-        })
-        .collect();
-    let humidity_location_maps: SeedMapVec = split_lines[humidity_to_location_pos + 1..]
+        .fold(seeds.to_vec(), |ranges, maps| map_ranges(maps, ranges))
         .iter()
-        .map(|x| {
-            let elements: Vec<u64> = x.split(" ").map(|i| i.parse::<u64>().unwrap()).collect();
-            match elements[..] {
-                [dest_start, src_start, length] => SeedMapping {
-                    dest_start,
-                    src_start,
-                    length,
-                },
-                _ => panic!("Found non-3 length mapping"),
-            }
-        })
-        .collect();
-    (
-        seed_numbers,
-        seed_soil_maps,
-        soil_fertilizer_maps,
-        fertilizer_water_maps,
-        water_light_maps,
-        light_temperature_maps,
-        temperature_humidity_maps,
-        humidity_location_maps,
-    )
+        .filter(|x| !x.is_empty())
+        .map(|x| x.start)
+        .min()
 }
 
-pub fn day5_p1() {
+pub fn day5_p1(almanac: Almanac) -> SolveResult {
     let (
         seed_numbers,
         seed_soil_maps,
@@ -209,7 +150,7 @@ pub fn day5_p1() {
         light_temperature_maps,
         temperature_humidity_maps,
         humidity_location_maps,
-    ) = read_day5_input();
+    ) = almanac;
 
     let mut locations: Vec<u64> = vec![];
 
@@ -225,10 +166,10 @@ pub fn day5_p1() {
         let o7 = humidity_location_maps.map_sequential(&o6);
         locations.push(o7);
     }
-    println!("Minimum location: {:#?}", locations.iter().min());
+    Ok(locations.iter().min().ok_or("no seeds")?.to_string())
 }
 
-pub fn day5_p2() {
+pub fn day5_p2(almanac: Almanac) -> SolveResult {
     let (
         seed_numbers,
         seed_soil_maps,
@@ -238,33 +179,53 @@ pub fn day5_p2() {
         light_temperature_maps,
         temperature_humidity_maps,
         humidity_location_maps,
-    ) = read_day5_input();
+    ) = almanac;
 
-    let full_seed_numbers = seed_numbers
+    // the seeds come in (start, length) pairs covering billions of seeds, so map the
+    // ranges whole rather than one seed at a time
+    let seeds: Vec<Range<u64>> = seed_numbers
         .chunks_exact(2)
-        .flat_map(|x| x[0]..x[0] + x[1])
-        .collect::<Vec<u64>>();
-
-    println!("Expanded to {:#?} total seeds", &full_seed_numbers.len());
+        .map(|x| x[0]..x[0] + x[1])
+        .collect();
+    debug!("Mapping {} seed ranges", seeds.len());
+    let stages = [
+        &seed_soil_maps,
+        &soil_fertilizer_maps,
+        &fertilizer_water_maps,
+        &water_light_maps,
+        &light_temperature_maps,
+        &temperature_humidity_maps,
+        &humidity_location_maps,
+    ];
+    Ok(lowest_location_by_ranges(&seeds, &stages)
+        .ok_or("no seeds")?
+        .to_string())
+}
 
-    let mut locations: Vec<u64> = vec![];
-    thread::scope(|s| {
-        let mut threads: Vec<thread::ScopedJoinHandle<u64>> = Vec::new();
-        for seed in &full_seed_numbers {
-            // NB this whole solution is immensely slow because we iterate over seeds applying anything
-            // suitable to fix them instead of going mapping-back
-            let t = s.spawn(|| {
-                let o1 = seed_soil_maps.map_sequential(seed);
-                let o2 = soil_fertilizer_maps.map_sequential(&o1);
-                let o3 = fertilizer_water_maps.map_sequential(&o2);
-                let o4 = water_light_maps.map_sequential(&o3);
-                let o5 = light_temperature_maps.map_sequential(&o4);
-                let o6 = temperature_humidity_maps.map_sequential(&o5);
-                humidity_location_maps.map_sequential(&o6)
-            });
-            threads.push(t);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    proptest! {
+        #[test]
+        fn mapping_is_a_bijection_on_its_source(
+            dest_start in 0u64..1 << 40,
+            src_start in 0u64..1 << 40,
+            length in 1u64..2000,
+        ) {
+            let mapping = SeedMapping { dest_start, src_start, length };
+            let mapped: HashSet<u64> = (src_start..src_start + length)
+                .map(|x| mapping.map_seed(&x))
+                .collect();
+            let expected: HashSet<u64> = (dest_start..dest_start + length).collect();
+            prop_assert_eq!(mapped, expected);
+            // and leaves everything either side alone
+            if src_start > 0 {
+                prop_assert_eq!(mapping.map_seed(&(src_start - 1)), src_start - 1);
+            }
+            prop_assert_eq!(mapping.map_seed(&(src_start + length)), src_start + length);
         }
-        locations = threads.into_iter().map(|x| x.join().unwrap()).collect();
-        println!("Minimum location: {:#?}", locations.iter().min());
-    })
+    }
 }
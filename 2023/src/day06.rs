@@ -1,64 +1,27 @@
-fn get_day6_input_p1() -> Vec<(u64, u64)> {
-    let mut input_lines = include_str!("../examples/day6_input.txt").lines();
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
 
-    let time_dists: Vec<(u64, u64)> = input_lines
-        .next()
-        .unwrap()
-        .split(":")
-        .skip(1)
-        .flat_map(|x| {
-            x.trim()
-                .split_ascii_whitespace()
-                .map(|i| i.parse::<u64>().unwrap())
-        })
-        .zip(
-            input_lines
-                .next()
-                .unwrap()
-                .split(":")
-                .skip(1)
-                .flat_map(|x| {
-                    x.trim()
-                        .split_ascii_whitespace()
-                        .map(|i| i.parse::<u64>().unwrap())
-                }),
-        )
-        .collect();
-    time_dists
+// the numbers after "Time:" and "Distance:" as raw text
+fn get_day6_fields(input: &str) -> Result<(&str, &str), ParseError> {
+    let [(_, times), (_, dists)] = parse::array(parse::key_values(input, ":")?)?;
+    Ok((times, dists))
 }
 
-fn get_day6_input_p2() -> (u64, u64) {
-    let mut input_lines = include_str!("../examples/day6_input.txt").lines();
-
-    let time: u64 = input_lines
-        .next()
-        .unwrap()
-        .split(":")
-        .skip(1)
-        .next()
-        .unwrap()
-        .trim()
-        .split_ascii_whitespace()
-        .collect::<Vec<&str>>()
-        .join("")
-        .parse::<u64>()
-        .unwrap();
-
-    let dist: u64 = input_lines
-        .next()
-        .unwrap()
-        .split(":")
-        .skip(1)
-        .next()
-        .unwrap()
-        .trim()
-        .split_ascii_whitespace()
-        .collect::<Vec<&str>>()
-        .join("")
-        .parse::<u64>()
-        .unwrap();
+pub(crate) fn get_day6_input_p1(input: &str) -> Result<Vec<(u64, u64)>, ParseError> {
+    let (times, dists) = get_day6_fields(input)?;
+    Ok(parse::ints(times)?
+        .into_iter()
+        .zip(parse::ints(dists)?)
+        .collect())
+}
 
-    (time, dist)
+// part 2 ignores the spaces and reads each line as one big number
+pub(crate) fn get_day6_input_p2(input: &str) -> Result<(u64, u64), ParseError> {
+    let (time, dist) = get_day6_fields(input)?;
+    Ok((
+        parse::value(&time.replace(' ', ""))?,
+        parse::value(&dist.replace(' ', ""))?,
+    ))
 }
 
 /// Get distance based on pressing and total time
@@ -68,8 +31,7 @@ fn get_distance(total_time: u64, time_pressed: u64) -> u64 {
     time_pressed * time_not_pressed
 }
 
-pub fn day6_p1() {
-    let time_dists = get_day6_input_p1();
+pub fn day6_p1(time_dists: Vec<(u64, u64)>) -> SolveResult {
     let winning_ways: Vec<usize> = time_dists
         .into_iter()
         .map(|(time, dist)| {
@@ -79,17 +41,13 @@ pub fn day6_p1() {
                 .count()
         })
         .collect();
-    println!(
-        "Product of ways to win: {:#?}",
-        winning_ways.iter().product::<usize>()
-    );
+    Ok(winning_ways.iter().product::<usize>().to_string())
 }
 
-pub fn day6_p2() {
-    let (time, dist) = get_day6_input_p2();
+pub fn day6_p2((time, dist): (u64, u64)) -> SolveResult {
     let winning_ways = (0..=time)
         .map(|x| get_distance(time, x))
         .filter(|x| x > &dist)
         .count();
-    println!("Ways to win: {:#?}", winning_ways);
+    Ok(winning_ways.to_string())
 }
@@ -1,12 +1,15 @@
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
 use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
-enum Part {
+pub(crate) enum Part {
     One,
     Two,
 }
-#[derive(Debug, Hash, Clone)]
-enum CamelCard {
+#[derive(Debug, Clone)]
+pub(crate) enum CamelCard {
     LetterP1(char),
     Number(u16),
     LetterP2(char),
@@ -25,7 +28,7 @@ impl CamelCard {
             CamelCard::LetterP2('Q') => 12,
             CamelCard::LetterP2('K') => 13,
             CamelCard::LetterP2('A') => 14,
-            CamelCard::Number(x) => x.clone(),
+            CamelCard::Number(x) => *x,
             _ => panic!("Did not expect card value {self:#?}"),
         }
     }
@@ -38,6 +41,13 @@ impl PartialEq for CamelCard {
 }
 impl Eq for CamelCard {}
 
+// hash on rank too so it agrees with the rank-based equality
+impl Hash for CamelCard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.card_rank().hash(state);
+    }
+}
+
 impl PartialOrd for CamelCard {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -117,7 +127,7 @@ impl CamelHand {
         frequencies.sort_by_key(|x| -(x.1 as i64));
 
         // top frequencies to determine hand type
-        match if frequencies.len() > 0 {
+        match if !frequencies.is_empty() {
             frequencies[0].1 + num_jokers
         } else {
             // fallback if all were jokers
@@ -193,17 +203,18 @@ impl Ord for CamelHand {
     }
 }
 
-fn get_day7_input(part: Part) -> Vec<([CamelCard; 5], u32)> {
-    let input_str = include_str!("../examples/day7_input.txt");
-    input_str
-        .lines()
-        .map(|x| {
-            let split_elements: Vec<&str> = x.split_ascii_whitespace().collect();
-            let camel_cards: [CamelCard; 5] = split_elements[0]
+pub(crate) fn get_day7_input(
+    input: &str,
+    part: Part,
+) -> Result<Vec<([CamelCard; 5], u32)>, ParseError> {
+    parse::lines(input, |x| {
+        let (cards, bid) = parse::key_value(x, " ")?;
+        let camel_cards: [CamelCard; 5] = parse::array(
+            cards
                 .chars()
                 .map(|c| {
                     if c.is_numeric() {
-                        CamelCard::Number(c.to_string().parse::<u16>().unwrap())
+                        CamelCard::Number(c.to_digit(10).unwrap() as u16)
                     } else {
                         match part {
                             Part::One => CamelCard::LetterP1(c),
@@ -211,17 +222,13 @@ fn get_day7_input(part: Part) -> Vec<([CamelCard; 5], u32)> {
                         }
                     }
                 })
-                .collect::<Vec<CamelCard>>()
-                .try_into()
-                .unwrap();
-            let bid = split_elements[1].parse::<u32>().unwrap();
-            (camel_cards, bid)
-        })
-        .collect()
+                .collect(),
+        )?;
+        Ok((camel_cards, parse::value(bid)?))
+    })
 }
 
-pub fn day7_p1() {
-    let inputs = get_day7_input(Part::One);
+pub(crate) fn day7_p1(inputs: Vec<([CamelCard; 5], u32)>) -> SolveResult {
     let mut hands: Vec<(CamelHand, u32)> = inputs
         .iter()
         .map(|x| (CamelHand::from_cards_p1(&x.0), x.1))
@@ -233,11 +240,10 @@ pub fn day7_p1() {
         .enumerate()
         .map(|(idx, x)| (idx as u32 + 1) * x.1)
         .sum::<u32>();
-    println!("total winnings: {:#?}", total_winnings);
+    Ok(total_winnings.to_string())
 }
 
-pub fn day7_p2() {
-    let inputs = get_day7_input(Part::Two);
+pub(crate) fn day7_p2(inputs: Vec<([CamelCard; 5], u32)>) -> SolveResult {
     let mut hands: Vec<(CamelHand, u32)> = inputs
         .iter()
         .map(|x| (CamelHand::from_cards_p2(&x.0), x.1))
@@ -249,5 +255,41 @@ pub fn day7_p2() {
         .enumerate()
         .map(|(idx, x)| (idx as u32 + 1) * x.1)
         .sum::<u32>();
-    println!("total winnings: {:#?}", total_winnings);
+    Ok(total_winnings.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn hand(cards: &str, joker_rules: bool) -> CamelHand {
+        let part = if joker_rules { Part::Two } else { Part::One };
+        let [(cards, _)] =
+            parse::array(get_day7_input(&format!("{cards} 1"), part).unwrap()).unwrap();
+        match joker_rules {
+            false => CamelHand::from_cards_p1(&cards),
+            true => CamelHand::from_cards_p2(&cards),
+        }
+    }
+
+    const CARDS: &str = "[23456789TJQKA]{5}";
+
+    proptest! {
+        #[test]
+        fn hand_ordering_is_total_and_matches_eq(
+            a in CARDS,
+            b in CARDS,
+            c in CARDS,
+            joker_rules: bool,
+        ) {
+            let [a, b, c] = [&a, &b, &c].map(|x| hand(x, joker_rules));
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            prop_assert_eq!(a == b, a.cmp(&b) == Ordering::Equal);
+            prop_assert_eq!(a.cmp(&a), Ordering::Equal);
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+        }
+    }
 }
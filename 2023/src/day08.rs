@@ -1,63 +1,62 @@
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
+use log::{debug, trace};
 use num::integer::lcm;
 use std::collections::HashMap;
 
-fn get_day8_input() -> (Vec<usize>, HashMap<&'static str, [&'static str; 2]>) {
-    let mut input_str_lines = include_str!("../examples/day8_input.txt").lines();
-    let lr_instructions: Vec<usize> = input_str_lines
-        .next()
-        .unwrap()
+type Network<'a> = HashMap<&'a str, [&'a str; 2]>;
+
+// instructions as 0/1 indexes into each node's [left, right] pair, plus the "AAA = (BBB, CCC)" nodes
+pub(crate) fn get_day8_input(input: &str) -> Result<(Vec<usize>, Network<'_>), ParseError> {
+    let [instructions, nodes] = parse::array(parse::blocks(input))?;
+    let lr_instructions: Vec<usize> = instructions
         .trim()
         .chars()
         .map(|x| match x {
-            'L' => 0,
-            'R' => 1,
-            _ => panic!("Did not expect direction {x}"),
+            'L' => Ok(0),
+            'R' => Ok(1),
+            _ => Err(ParseError::invalid(&x.to_string(), "L or R")),
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    let slot_map: HashMap<&str, [&str; 2]> = input_str_lines
-        .filter(|x| x.trim() != "")
-        .map(|x| {
-            let split_line: Vec<&str> = x.trim().split(" = ").collect();
-            let line_sides: Vec<&str> = split_line[1][1..split_line[1].len() - 1]
-                .split(", ")
-                .collect();
-            (split_line[0], [line_sides[0], line_sides[1]])
+    let slot_map: Network = parse::key_values(nodes, "=")?
+        .into_iter()
+        .map(|(node, sides)| {
+            let (left, right) = parse::split_pair(parse::delimited(sides, "(", ")")?, ", ")?;
+            Ok((node, [left, right]))
         })
-        .collect();
+        .collect::<Result<_, ParseError>>()?;
 
-    (lr_instructions, slot_map)
+    Ok((lr_instructions, slot_map))
 }
 
-pub fn day8_p1() {
-    let (lr_instructions, slot_map) = get_day8_input();
-    println!("{:#?}", lr_instructions);
-    println!("{:#?}", slot_map);
+pub(crate) fn day8_p1((lr_instructions, slot_map): (Vec<usize>, Network)) -> SolveResult {
+    debug!("instructions: {:?}", lr_instructions);
+    trace!("network: {:#?}", slot_map);
     let mut cur_pos = "AAA";
     let mut iteration = 0;
 
     for d in lr_instructions.iter().cycle() {
         iteration += 1;
-        println!("before iteration={iteration}, cur_pos={cur_pos}");
+        trace!("before iteration={iteration}, cur_pos={cur_pos}");
         cur_pos = slot_map.get(cur_pos).unwrap()[*d];
-        println!("after iteration={iteration}, cur_pos={cur_pos}");
+        trace!("after iteration={iteration}, cur_pos={cur_pos}");
         if cur_pos == "ZZZ" {
             break;
         }
     }
-    println!("Total steps: {iteration}");
+    Ok(iteration.to_string())
 }
 
-pub fn day8_p2() {
-    let (lr_instructions, slot_map) = get_day8_input();
-    println!("{:#?}", lr_instructions);
-    println!("{:#?}", slot_map);
-    let cur_positions: Vec<&'static str> = slot_map
+pub(crate) fn day8_p2((lr_instructions, slot_map): (Vec<usize>, Network)) -> SolveResult {
+    debug!("instructions: {:?}", lr_instructions);
+    trace!("network: {:#?}", slot_map);
+    let cur_positions: Vec<&str> = slot_map
         .keys()
-        .filter(|x| x.ends_with("A"))
-        .map(|x| *x)
+        .filter(|x| x.ends_with('A'))
+        .copied()
         .collect();
-    println!("Using {} positions", cur_positions.len());
+    debug!("Using {} positions", cur_positions.len());
 
     let mut iterations: Vec<i64> = Vec::new();
     for i in cur_positions {
@@ -71,8 +70,8 @@ pub fn day8_p2() {
                 break;
             }
         }
-        println!("initial position {i}, cycle number: {iteration}");
+        debug!("initial position {i}, cycle number: {iteration}");
     }
     let lowest_mult = iterations.into_iter().fold(1, lcm);
-    println!("Total steps lcm: {}", lowest_mult);
+    Ok(lowest_mult.to_string())
 }
@@ -1,17 +1,11 @@
-fn get_day9_input() -> Vec<Vec<i32>> {
-    let input_str: Vec<Vec<i32>> = include_str!("../examples/day9_input.txt")
-        .lines()
-        .map(|x| {
-            x.split(" ")
-                .map(|c| c.parse::<i32>().expect("Couldn't parse value"))
-                .collect::<Vec<i32>>()
-        })
-        .collect();
-    input_str
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
+
+pub(crate) fn get_day9_input(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    parse::lines(input, parse::ints)
 }
 
-pub fn day9_p1() {
-    let inputs = get_day9_input();
+pub fn day9_p1(inputs: Vec<Vec<i32>>) -> SolveResult {
     let mut sum_preds = 0;
     for l in inputs {
         let mut differenced_vecs: Vec<Vec<i32>> = vec![l.clone()];
@@ -36,11 +30,10 @@ pub fn day9_p1() {
             .sum::<i32>();
         sum_preds += sum_pred;
     }
-    println!("Sum of predictions: {}", sum_preds);
+    Ok(sum_preds.to_string())
 }
 
-pub fn day9_p2() {
-    let inputs = get_day9_input();
+pub fn day9_p2(inputs: Vec<Vec<i32>>) -> SolveResult {
     let mut sum_preds = 0;
     for l in inputs {
         let mut differenced_vecs: Vec<Vec<i32>> = vec![l.clone()];
@@ -68,5 +61,5 @@ pub fn day9_p2() {
             .fold(0, |a, x| x - a);
         sum_preds += sum_pred
     }
-    println!("Sum of predictions: {}", sum_preds);
+    Ok(sum_preds.to_string())
 }
@@ -1,157 +1,114 @@
+use crate::grid::Grid;
+use crate::parse::{self, ParseError};
+use crate::point::{
+    Direction::{self, East, North, South, West},
+    Point,
+};
+use aoc_core::SolveResult;
+use log::{debug, trace};
 use std::collections::HashSet;
 
 #[derive(Debug)]
-struct Pipe {
+pub(crate) struct Pipe {
     c: char,
-    x: i32,
-    y: i32,
-    directions: Vec<(i32, i32)>,
+    directions: Vec<Direction>,
 }
-const NORTH: (i32, i32) = (-1, 0);
-const SOUTH: (i32, i32) = (1, 0);
-const EAST: (i32, i32) = (0, 1);
-const WEST: (i32, i32) = (0, -1);
 
-impl Pipe {
-    fn from_letter(x: i32, y: i32, c: char) -> Self {
+impl TryFrom<char> for Pipe {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         let directions = match c {
-            '|' => vec![NORTH, SOUTH],
-            '-' => vec![EAST, WEST],
-            'L' => vec![NORTH, EAST],
-            'J' => vec![NORTH, WEST],
-            '7' => vec![SOUTH, WEST],
-            'F' => vec![SOUTH, EAST],
+            '|' => vec![North, South],
+            '-' => vec![East, West],
+            'L' => vec![North, East],
+            'J' => vec![North, West],
+            '7' => vec![South, West],
+            'F' => vec![South, East],
             '.' => vec![],
-            'S' => vec![NORTH, SOUTH, EAST, WEST],
-            _ => panic!("Didn't expect character {c}"),
+            'S' => Direction::ALL.to_vec(),
+            _ => return Err(ParseError::invalid(&c.to_string(), "a pipe")),
         };
-        Pipe {
-            c,
-            x,
-            y,
-            directions,
-        }
+        Ok(Pipe { c, directions })
     }
+}
 
-    fn connections(&self, row_range: &(i32, i32), col_range: &(i32, i32)) -> Vec<(i32, i32)> {
-        self.directions
-            .iter()
-            .map(|(r, c)| (self.x + r, self.y + c))
-            .filter(|(x, y)| {
-                row_range.0 <= *x && *x < row_range.1 && col_range.0 <= *y && *y < col_range.1
-            })
-            .collect()
-    }
-    fn coord(&self) -> (i32, i32) {
-        (self.x, self.y)
-    }
+// positions this pipe at `coord` connects to, if they're inside the grid
+fn connections(inputs: &Grid<Pipe>, coord: Point) -> Vec<Point> {
+    inputs[coord]
+        .directions
+        .iter()
+        .filter_map(|x| inputs.step(&coord, *x))
+        .collect()
 }
 
-fn get_day10_input() -> Vec<Vec<Pipe>> {
-    let input_str = include_str!("../examples/day10_input.txt");
-    let pipes: Vec<Vec<Pipe>> = input_str
-        .lines()
-        .enumerate()
-        .map(|(row, x)| {
-            x.chars()
-                .enumerate()
-                .map(|(col, c)| Pipe::from_letter(row as i32, col as i32, c))
-                .collect::<Vec<Pipe>>()
-        })
-        .collect();
-    pipes
+pub(crate) fn get_day10_input(input: &str) -> Result<Grid<Pipe>, ParseError> {
+    parse::grid(input)
 }
 
-pub fn day10_p1() {
-    let inputs = get_day10_input();
+pub(crate) fn day10_p1(inputs: Grid<Pipe>) -> SolveResult {
     let start_point = inputs
         .iter()
-        .flatten()
-        .filter(|x| x.c == 'S')
-        .next()
-        .unwrap();
+        .find(|(_, x)| x.c == 'S')
+        .ok_or("no start tile")?
+        .0;
 
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
+    let mut visited: HashSet<Point> = HashSet::new();
 
-    let row_range: (i32, i32) = (0, inputs.len() as i32);
-    let col_range: (i32, i32) = (0, inputs[0].len() as i32);
-
-    let mut cur_point = start_point
+    let mut cur_point = connections(&inputs, start_point)
         // check all valid connections from the start point
-        .connections(&row_range, &col_range)
-        .iter()
-        // pull out each connection from the inputs
-        .map(|x| &inputs[x.0 as usize][x.1 as usize])
+        .into_iter()
         // filter down to only those that ALSO connect back to start
-        .filter(|x| {
-            x.connections(&row_range, &col_range)
-                .contains(&(start_point.x, start_point.y))
-        })
-        .next()
+        .find(|x| connections(&inputs, *x).contains(&start_point))
         .unwrap();
 
-    let mut num_steps = 0;
+    let mut num_steps: usize = 0;
     // loop till we get back to start
     loop {
-        println!("Visiting {} at {:?}", cur_point.c, cur_point.coord());
+        trace!("Visiting {} at {:?}", inputs[cur_point].c, cur_point);
         num_steps += 1;
         // add current point to visited set
-        visited.insert(cur_point.coord());
+        visited.insert(cur_point);
 
         // find connections not already visited
-        let new_connections: Vec<(i32, i32)> = cur_point
-            .connections(&row_range, &col_range)
+        let new_connections: Vec<Point> = connections(&inputs, cur_point)
             .into_iter()
-            .filter(|x| !(visited.contains(x) || &inputs[x.0 as usize][x.1 as usize].c == &'.'))
+            .filter(|x| !(visited.contains(x) || inputs[*x].c == '.'))
             .collect();
 
-        if new_connections.len() == 1
-            && (new_connections[0].0, new_connections[0].1) == start_point.coord()
-        {
-            println!("Reached origin after {num_steps} steps");
+        if new_connections.len() == 1 && new_connections[0] == start_point {
+            debug!("Reached origin after {num_steps} steps");
             break;
         }
-        let new_connections: Vec<(i32, i32)> = new_connections
+        let new_connections: Vec<Point> = new_connections
             .into_iter()
-            .filter(|x| *x != start_point.coord())
+            .filter(|x| *x != start_point)
             .collect();
 
-        cur_point = &inputs[new_connections[0].0 as usize][new_connections[0].1 as usize];
+        cur_point = new_connections[0];
     }
 
     // Maximum distance away is floordiv of total loop steps, +1 if odd number total
-    let max_dist = (num_steps / 2) + if num_steps % 2 == 0 { 0 } else { 1 };
-    println!("Maximum distance: {max_dist}");
+    let max_dist = num_steps.div_ceil(2);
+    Ok(max_dist.to_string())
 }
 
-pub fn day10_p2() {
+pub(crate) fn day10_p2(inputs: Grid<Pipe>) -> SolveResult {
     // use same as p1 to define our path, contained in the visited set
     // but use vec for visit to keep the points ordered
-    let inputs = get_day10_input();
     let start_point = inputs
         .iter()
-        .flatten()
-        .filter(|x| x.c == 'S')
-        .next()
-        .unwrap();
+        .find(|(_, x)| x.c == 'S')
+        .ok_or("no start tile")?
+        .0;
 
-    let mut visited: Vec<(i32, i32)> = Vec::new();
+    let mut visited: Vec<Point> = Vec::new();
 
-    let row_range: (i32, i32) = (0, inputs.len() as i32);
-    let col_range: (i32, i32) = (0, inputs[0].len() as i32);
-
-    let mut cur_point = start_point
+    let mut cur_point = connections(&inputs, start_point)
         // check all valid connections from the start point
-        .connections(&row_range, &col_range)
-        .iter()
-        // pull out each connection from the inputs
-        .map(|x| &inputs[x.0 as usize][x.1 as usize])
+        .into_iter()
         // filter down to only those that ALSO connect back to start
-        .filter(|x| {
-            x.connections(&row_range, &col_range)
-                .contains(&(start_point.x, start_point.y))
-        })
-        .next()
+        .find(|x| connections(&inputs, *x).contains(&start_point))
         .unwrap();
 
     let mut num_steps = 0;
@@ -159,43 +116,60 @@ pub fn day10_p2() {
     loop {
         num_steps += 1;
         // add current point to visited set
-        visited.push(cur_point.coord());
+        visited.push(cur_point);
 
         // find connections not already visited
-        let new_connections: Vec<(i32, i32)> = cur_point
-            .connections(&row_range, &col_range)
+        let new_connections: Vec<Point> = connections(&inputs, cur_point)
             .into_iter()
-            .filter(|x| !(visited.contains(x) || &inputs[x.0 as usize][x.1 as usize].c == &'.'))
+            .filter(|x| !(visited.contains(x) || inputs[*x].c == '.'))
             .collect();
 
-        if new_connections.len() == 1
-            && (new_connections[0].0, new_connections[0].1) == start_point.coord()
-        {
-            visited.push(start_point.coord());
-            println!("Reached origin after {num_steps} steps");
+        if new_connections.len() == 1 && new_connections[0] == start_point {
+            visited.push(start_point);
+            debug!("Reached origin after {num_steps} steps");
             break;
         }
-        let new_connections: Vec<(i32, i32)> = new_connections
+        let new_connections: Vec<Point> = new_connections
             .into_iter()
-            .filter(|x| *x != start_point.coord())
+            .filter(|x| *x != start_point)
             .collect();
 
-        cur_point = &inputs[new_connections[0].0 as usize][new_connections[0].1 as usize];
+        cur_point = new_connections[0];
     }
 
     // shoelace formula for area based on the exterior points we visited: https://en.wikipedia.org/wiki/Shoelace_formula
     let mut area: f64 = 0.0;
     for (idx, val) in visited.iter().enumerate() {
+        let next = visited.get(idx + 1).unwrap_or(&visited[0]);
         // default behavior needs items n and n+1, but need to loop over to first element at end
         // y_component is y_n + y_(n+1)
-        let y_component = val.1 + visited.get(idx + 1).unwrap_or(&visited[0]).1;
+        let y_component = (val.col + next.col) as f64;
         // x_component is x_n - x_(n+1)
-        let x_component = val.0 - visited.get(idx + 1).unwrap_or(&visited[0]).0;
-        area += 0.5 * y_component as f64 * x_component as f64;
+        let x_component = val.row as f64 - next.row as f64;
+        area += 0.5 * y_component * x_component;
     }
     area = area.abs();
 
     // pick's theorem to back out the number of interior points from area and exterior: https://en.wikipedia.org/wiki/Pick's_theorem
     let interior_points = area + 1.0 - visited.len() as f64 / 2.0;
-    println!("Number of interior points: {}", interior_points);
+    Ok(interior_points.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+    use crate::rng::Rng;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn interior_fits_in_the_grid(seed: u64, size in 3usize..25) {
+            let input = generate::day10(&mut Rng::new(seed), size);
+            let grid = get_day10_input(&input).unwrap();
+            let (width, height) = (grid.width(), grid.height());
+            let interior: usize = day10_p2(grid).unwrap().parse().unwrap();
+            prop_assert!(interior <= width * height);
+        }
+    }
 }
@@ -1,136 +1,98 @@
-use std::fs;
+use crate::grid::Grid;
+use crate::parse::{self, ParseError};
+use crate::point::Point;
+use aoc_core::SolveResult;
+use log::{debug, trace};
 
-fn get_day11_input(path: &str) -> Vec<Vec<bool>> {
-    let input_str = fs::read_to_string(path).unwrap();
-    input_str
-        .lines()
-        .map(|l| l.chars().map(|c| c == '#').collect())
-        .collect()
+pub(crate) fn get_day11_input(input: &str) -> Result<Grid<bool>, ParseError> {
+    Ok(parse::grid::<char>(input)?.map(|&c| c == '#'))
 }
 
-pub fn day11_p1() {
-    let mut galaxy_grid = get_day11_input("examples/day11_input.txt");
-    let col_count = galaxy_grid[0].len();
-
-    // empty rows are simple iteration where those where all are false
-    let empty_rows: Vec<usize> = galaxy_grid
-        .iter()
+// empty rows/cols are those where every element is false
+fn empty_rows(galaxy_grid: &Grid<bool>) -> Vec<usize> {
+    galaxy_grid
+        .rows()
         .enumerate()
         .filter_map(|(idx, row)| {
-            if row.iter().all(|x| *x == false) {
+            if row.iter().all(|x| !*x) {
                 Some(idx)
             } else {
                 None
             }
         })
-        .collect();
-    // iterate over known columns
-    let empty_cols: Vec<usize> = (0..col_count)
-        .filter_map(|col_idx| {
-            // for each column index, pull the right element from every row and check if false
-            if galaxy_grid.iter().map(|r| r[col_idx]).all(|x| x == false) {
-                Some(col_idx)
-            } else {
-                None
-            }
-        })
-        .collect();
+        .collect()
+}
 
-    // add empty rows with the existing ones
-    // enumeration is to add offsets for the extra positions already added
-    let empty_row_vec: Vec<Vec<bool>> = vec![(0..col_count).map(|_| false).collect()];
-    for (idx, i) in empty_rows.iter().enumerate() {
-        galaxy_grid.splice(i + idx..i + idx, empty_row_vec.clone());
-    }
-    // add empty cols with the existing ones
-    // enumeration is to add offsets for the extra positions already added
-    for (idx, i) in empty_cols.iter().enumerate() {
-        println!("Adding empty col after {i}");
-        // within each one we have to splice within each row
-        for row in galaxy_grid.iter_mut() {
-            row.splice(i + idx..i + idx, vec![false]);
+fn empty_cols(galaxy_grid: &Grid<bool>) -> Vec<usize> {
+    galaxy_grid
+        .columns()
+        .enumerate()
+        .filter_map(|(idx, mut col)| if col.all(|x| !*x) { Some(idx) } else { None })
+        .collect()
+}
+
+fn galaxy_positions(galaxy_grid: &Grid<bool>) -> Vec<Point> {
+    galaxy_grid
+        .iter()
+        .filter_map(|(pos, &c)| if c { Some(pos) } else { None })
+        .collect()
+}
+
+pub fn day11_p1(galaxy_grid: Grid<bool>) -> SolveResult {
+    let empty_rows = empty_rows(&galaxy_grid);
+    let empty_cols = empty_cols(&galaxy_grid);
+
+    // rebuild the grid with every empty row and col doubled up
+    let mut expanded_rows: Vec<Vec<bool>> = vec![];
+    for (row_idx, row) in galaxy_grid.rows().enumerate() {
+        let mut expanded_row: Vec<bool> = vec![];
+        for (col_idx, &c) in row.iter().enumerate() {
+            expanded_row.push(c);
+            if empty_cols.contains(&col_idx) {
+                expanded_row.push(false);
+            }
+        }
+        if empty_rows.contains(&row_idx) {
+            expanded_rows.push(expanded_row.clone());
         }
+        expanded_rows.push(expanded_row);
+    }
+    for i in &empty_cols {
+        trace!("Adding empty col after {i}");
     }
+    let galaxy_grid = Grid::from_rows(expanded_rows);
 
     // print grid to inspect
-    for r in &galaxy_grid {
-        println!(
-            "{}",
-            r.iter()
-                .map(|&x| if x { "#" } else { "." })
-                .collect::<Vec<&str>>()
-                .join("")
-        );
-    }
+    trace!(
+        "expanded grid:\n{}",
+        galaxy_grid.map(|&x| if x { '#' } else { '.' })
+    );
 
     // collect positions
-    let galaxy_positions: Vec<(usize, usize)> = galaxy_grid
-        .iter()
-        .enumerate()
-        .flat_map(|(row_idx, r)| {
-            r.iter()
-                .enumerate()
-                .filter_map(|(col_idx, &c)| if c { Some((row_idx, col_idx)) } else { None })
-                .collect::<Vec<(usize, usize)>>()
-        })
-        .collect();
+    let galaxy_positions = galaxy_positions(&galaxy_grid);
 
     let mut dists: Vec<usize> = vec![];
     for i in 0..galaxy_positions.len() {
         for j in i + 1..galaxy_positions.len() {
-            let dist = galaxy_positions[i].0.abs_diff(galaxy_positions[j].0)
-                + galaxy_positions[i].1.abs_diff(galaxy_positions[j].1);
+            let dist = galaxy_positions[i].manhattan(&galaxy_positions[j]);
             dists.push(dist)
         }
     }
 
-    println!("Sum of distances: {}", dists.iter().sum::<usize>());
+    Ok(dists.iter().sum::<usize>().to_string())
 }
 
-pub fn day11_p2() {
-    let galaxy_grid = get_day11_input("examples/day11_input.txt");
-    let col_count = galaxy_grid[0].len();
-
-    // empty rows are simple iteration where those where all are false
-    let empty_rows: Vec<usize> = galaxy_grid
-        .iter()
-        .enumerate()
-        .filter_map(|(idx, row)| {
-            if row.iter().all(|x| *x == false) {
-                Some(idx)
-            } else {
-                None
-            }
-        })
-        .collect();
-    // iterate over known columns
-    let empty_cols: Vec<usize> = (0..col_count)
-        .filter_map(|col_idx| {
-            // for each column index, pull the right element from every row and check if false
-            if galaxy_grid.iter().map(|r| r[col_idx]).all(|x| x == false) {
-                Some(col_idx)
-            } else {
-                None
-            }
-        })
-        .collect();
+pub fn day11_p2(galaxy_grid: Grid<bool>) -> SolveResult {
+    let empty_rows = empty_rows(&galaxy_grid);
+    let empty_cols = empty_cols(&galaxy_grid);
 
     let space_multiplier = 1_000_000 - 1;
 
     // collect positions
-    let galaxy_positions: Vec<(usize, usize)> = galaxy_grid
-        .iter()
-        .enumerate()
-        .flat_map(|(row_idx, r)| {
-            r.iter()
-                .enumerate()
-                .filter_map(|(col_idx, &c)| if c { Some((row_idx, col_idx)) } else { None })
-                .collect::<Vec<(usize, usize)>>()
-        })
-        .collect();
+    let galaxy_positions = galaxy_positions(&galaxy_grid);
 
-    println!("empty rows: {empty_rows:#?}");
-    println!("empty cols: {empty_cols:#?}");
+    debug!("empty rows: {empty_rows:?}");
+    debug!("empty cols: {empty_cols:?}");
 
     let distances: Vec<usize> = galaxy_positions
         .iter()
@@ -139,21 +101,20 @@ pub fn day11_p2() {
             galaxy_positions[idx + 1..]
                 .iter()
                 .map(|g2| {
-                    g1.0.abs_diff(g2.0)
-                        + g1.1.abs_diff(g2.1)
+                    g1.manhattan(g2)
                         + space_multiplier
                             * (empty_cols
                                 .iter()
-                                .filter(|&&x| x > g1.1.min(g2.1) && x < g1.1.max(g2.1))
+                                .filter(|&&x| x > g1.col.min(g2.col) && x < g1.col.max(g2.col))
                                 .count()
                                 + empty_rows
                                     .iter()
-                                    .filter(|&&x| x > g1.0.min(g2.0) && x < g1.0.max(g2.0))
+                                    .filter(|&&x| x > g1.row.min(g2.row) && x < g1.row.max(g2.row))
                                     .count())
                 })
                 .collect::<Vec<usize>>()
         })
         .collect();
 
-    println!("Sum of distances: {:#?}", distances.iter().sum::<usize>());
+    Ok(distances.iter().sum::<usize>().to_string())
 }
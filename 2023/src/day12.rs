@@ -1,6 +1,10 @@
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
+use log::{debug, trace};
+use num::{BigUint, One, Zero};
 use rayon::prelude::*;
 use std::collections::HashMap;
-use std::{fs, str::FromStr};
+use std::{error::Error, fmt, str::FromStr};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum RecordType {
@@ -12,6 +16,14 @@ pub enum RecordType {
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParseRecordError;
 
+impl fmt::Display for ParseRecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected a single '#', '.' or '?' character")
+    }
+}
+
+impl Error for ParseRecordError {}
+
 impl FromStr for RecordType {
     type Err = ParseRecordError;
 
@@ -51,53 +63,43 @@ impl CacheTuple<'_> {
     }
 }
 
-fn get_day12_input(path: &str) -> Vec<(Vec<RecordType>, Vec<usize>)> {
-    let input_str = fs::read_to_string(path).unwrap();
-    let input_groups: Vec<[String; 2]> = input_str
-        .lines()
-        .map(|l| {
-            l.split(" ")
-                .map(String::from)
-                .collect::<Vec<String>>()
-                .try_into()
-                .unwrap()
-        })
-        .collect();
-    input_groups
-        .iter()
-        .map(|x| {
-            let spring_records: Vec<RecordType> = x[0]
-                .chars()
-                .map(|x| x.to_string().parse().unwrap())
-                .collect();
-            let contig_groups: Vec<usize> = x[1].split(",").map(|c| c.parse().unwrap()).collect();
-            (spring_records, contig_groups)
-        })
-        .collect()
+// one row of springs with its list of contiguous damaged group sizes
+type SpringRow = (Vec<RecordType>, Vec<usize>);
+
+pub(crate) fn get_day12_input(input: &str) -> Result<Vec<SpringRow>, ParseError> {
+    parse::lines(input, |l| {
+        let (records, groups) = parse::split_pair(l, " ")?;
+        let spring_records: Vec<RecordType> = records
+            .chars()
+            .map(|x| parse::value(&x.to_string()))
+            .collect::<Result<_, _>>()?;
+        let contig_groups: Vec<usize> = parse::ints_sep(groups, ",")?;
+        Ok((spring_records, contig_groups))
+    })
 }
 
-fn count_groups(records: &[RecordType], groups: &[usize]) -> usize {
-    if records.len() == 0 {
+pub(crate) fn count_groups(records: &[RecordType], groups: &[usize]) -> BigUint {
+    if records.is_empty() {
         // if no records left but still groups, fail
-        if groups.len() > 0 {
-            return 0;
+        if !groups.is_empty() {
+            return BigUint::zero();
         // if no records left and no groups, succeed!
         } else {
-            return 1;
+            return BigUint::one();
         }
     }
-    if groups.len() == 0 {
+    if groups.is_empty() {
         // if no groups but more damaged still exist then no ways to make it
-        if records.iter().any(|x| *x == RecordType::Damaged) {
-            return 0;
+        if records.contains(&RecordType::Damaged) {
+            return BigUint::zero();
         } else {
-            return 1;
+            return BigUint::one();
         }
     }
 
     // main cases: need to run through rest of list
     // start accumulator
-    let mut result: usize = 0;
+    let mut result = BigUint::zero();
 
     // if we're operational or treating this unknown as optional, skip a step
     if let RecordType::Operational | RecordType::Unknown = records[0] {
@@ -117,7 +119,7 @@ fn count_groups(records: &[RecordType], groups: &[usize]) -> usize {
         {
             // when we hit the end of the list, pass an empty record list
             if groups[0] == records.len() {
-                result += count_groups(&vec![], &groups[1..])
+                result += count_groups(&[], &groups[1..])
             } else {
                 // otherwise skip a group and continue
                 result += count_groups(&records[groups[0] + 1..], &groups[1..])
@@ -128,37 +130,37 @@ fn count_groups(records: &[RecordType], groups: &[usize]) -> usize {
     result
 }
 
-fn count_groups_memo(
+pub(crate) fn count_groups_memo(
     records: &[RecordType],
     groups: &[usize],
-    cache: &mut HashMap<(String, String), usize>,
-) -> usize {
+    cache: &mut HashMap<(String, String), BigUint>,
+) -> BigUint {
     let hashkey = CacheTuple(records, groups).to_string();
     match cache.get(&hashkey) {
-        Some(x) => *x,
+        Some(x) => x.clone(),
         None => {
             let ans = {
-                if records.len() == 0 {
+                if records.is_empty() {
                     // if no records left but still groups, fail
-                    if groups.len() > 0 {
-                        return 0;
+                    if !groups.is_empty() {
+                        return BigUint::zero();
                     // if no records left and no groups, succeed!
                     } else {
-                        return 1;
+                        return BigUint::one();
                     }
                 }
-                if groups.len() == 0 {
+                if groups.is_empty() {
                     // if no groups but more damaged still exist then no ways to make it
-                    if records.iter().any(|x| *x == RecordType::Damaged) {
-                        return 0;
+                    if records.contains(&RecordType::Damaged) {
+                        return BigUint::zero();
                     } else {
-                        return 1;
+                        return BigUint::one();
                     }
                 }
 
                 // main cases: need to run through rest of list
                 // start accumulator
-                let mut result: usize = 0;
+                let mut result = BigUint::zero();
 
                 // if we're operational or treating this unknown as optional, skip a step
                 if let RecordType::Operational | RecordType::Unknown = records[0] {
@@ -178,7 +180,7 @@ fn count_groups_memo(
                     {
                         // when we hit the end of the list, pass an empty record list
                         if groups[0] == records.len() {
-                            result += count_groups_memo(&vec![], &groups[1..], cache)
+                            result += count_groups_memo(&[], &groups[1..], cache)
                         } else {
                             // otherwise skip a group and continue
                             result +=
@@ -189,66 +191,101 @@ fn count_groups_memo(
 
                 result
             };
-            cache.insert(hashkey, ans);
+            cache.insert(hashkey, ans.clone());
             ans
         }
     }
 }
 
-pub fn day12_p1() {
-    let input = get_day12_input("examples/day12_input.txt");
-    println!("{:#?}", input);
-    let mut cumsum = 0;
+/// Unfold a row by repeating the records `factor` times joined by `separator`,
+/// and the groups `factor` times back to back
+fn unfold(
+    records: &[RecordType],
+    groups: &[usize],
+    factor: usize,
+    separator: &RecordType,
+) -> (Vec<RecordType>, Vec<usize>) {
+    let mut unfolded_records: Vec<RecordType> = vec![];
+    let mut unfolded_groups: Vec<usize> = vec![];
+    for idx in 1..=factor {
+        unfolded_records.extend_from_slice(records);
+        if idx != factor {
+            unfolded_records.push(separator.clone());
+        }
+        unfolded_groups.extend_from_slice(groups);
+    }
+    (unfolded_records, unfolded_groups)
+}
+
+pub fn day12_p1(input: Vec<SpringRow>) -> SolveResult {
+    trace!("records: {:#?}", input);
+    let mut cumsum = BigUint::zero();
     for (idx, (records, groups)) in input.iter().enumerate() {
         let count = count_groups(records, groups);
-        println!("idx: {idx}, count: {count}");
+        debug!("idx: {idx}, count: {count}");
         cumsum += count;
     }
-    println!("Total: {cumsum}");
+    Ok(cumsum.to_string())
 }
 
-pub fn day12_p2() {
-    let input = get_day12_input("examples/day12_input.txt");
-    println!("{:#?}", input);
-    let counts: Vec<usize> = input
+pub fn day12_p2(
+    input: Vec<SpringRow>,
+    unfold_factor: usize,
+    separator: &RecordType,
+) -> SolveResult {
+    trace!("records: {:#?}", input);
+    let counts: Vec<BigUint> = input
         .par_iter()
         .enumerate()
         .map(|(idx, (records, groups))| {
-            let mut cache: HashMap<(String, String), usize> = HashMap::new();
-            let mut unfolded_records: Vec<RecordType> = vec![];
-            let mut unfolded_groups: Vec<usize> = vec![];
+            let mut cache: HashMap<(String, String), BigUint> = HashMap::new();
             // strip out consecutive operationals
             let records: Vec<RecordType> = records
                 .iter()
                 .enumerate()
                 .filter(|(inner_idx, r)| {
-                    if inner_idx != &0
+                    !(inner_idx != &0
                         && records[inner_idx - 1] == RecordType::Operational
-                        && r == &&RecordType::Operational
-                    {
-                        false
-                    } else {
-                        true
-                    }
+                        && r == &&RecordType::Operational)
                 })
                 .map(|x| x.1)
                 .cloned()
                 .collect();
-            for idx in 1..=5 {
-                for i in &records {
-                    unfolded_records.push(i.clone());
-                }
-                if idx != 5 {
-                    unfolded_records.push(RecordType::Unknown);
-                }
-                for i in groups {
-                    unfolded_groups.push(i.clone());
-                }
-            }
+            let (unfolded_records, unfolded_groups) =
+                unfold(&records, groups, unfold_factor, separator);
             let count = count_groups_memo(&unfolded_records, &unfolded_groups, &mut cache);
-            println!("idx: {idx}, count: {count}");
+            debug!("idx: {idx}, count: {count}");
             count
         })
         .collect();
-    println!("Total: {}", counts.iter().sum::<usize>());
+    Ok(counts.iter().sum::<BigUint>().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn cache_key_round_trips(records in "[#.?]{0,30}", groups in prop::collection::vec(1usize..20, 0..8)) {
+            let parsed: Vec<RecordType> = records
+                .chars()
+                .map(|x| x.to_string().parse().unwrap())
+                .collect();
+            let (record_string, group_string) = CacheTuple(&parsed, &groups).to_string();
+            prop_assert_eq!(&record_string, &records);
+
+            let reparsed: Vec<RecordType> = record_string
+                .chars()
+                .map(|x| RecordType::from_str(&x.to_string()).unwrap())
+                .collect();
+            prop_assert_eq!(reparsed, parsed);
+            let regrouped: Vec<usize> = group_string
+                .split_terminator(',')
+                .map(|x| x.parse().unwrap())
+                .collect();
+            prop_assert_eq!(regrouped, groups);
+        }
+    }
 }
@@ -1,94 +1,212 @@
-use std::fs;
+use crate::bitgrid::BitGrid;
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
+use log::{debug, log_enabled, Level};
 
-fn get_day13_input(path: &str) -> Vec<Vec<Vec<char>>> {
-    let input_str = fs::read_to_string(path).unwrap();
-    input_str
-        .split("\n\n")
-        .map(|x| x.lines().map(|l| l.chars().collect()).collect())
-        .collect()
+/// Which way a pattern is folded: `Horizontal` compares columns either side of the line
+/// (scoring the column index) and `Vertical` compares rows (scoring 100 times the row index)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
 }
 
-fn check_reflection_vertical(index: usize, square: &[Vec<char>]) -> bool {
-    (0..index)
-        .rev()
-        .zip(index..square.len())
-        .all(|(l, r)| square[l] == square[r])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflection {
+    pub orientation: Orientation,
+    pub index: usize,
+    pub differences: usize,
 }
 
-fn check_reflection_horizontal(index: usize, square: &[Vec<char>]) -> bool {
-    (0..index).rev().zip(index..square[0].len()).all(|(l, r)| {
-        square.iter().map(|x| x[l]).collect::<Vec<char>>()
-            == square.iter().map(|x| x[r]).collect::<Vec<char>>()
-    })
+impl Reflection {
+    fn score(&self) -> usize {
+        match self.orientation {
+            Orientation::Horizontal => self.index,
+            Orientation::Vertical => self.index * 100,
+        }
+    }
 }
 
-pub fn day13_p1() {
-    let input = get_day13_input("examples/day13_input.txt");
-    let mut running_sum = 0;
-    for i in input {
-        for idx in 1..i[0].len() {
-            if check_reflection_horizontal(idx, &i) {
-                running_sum += idx;
-                break;
-            }
+pub(crate) fn get_day13_input(input: &str) -> Result<Vec<BitGrid>, ParseError> {
+    parse::blocks(input)
+        .into_iter()
+        .map(|x| Ok(BitGrid::from(&parse::grid::<char>(x)?.map(|&c| c == '#'))))
+        .collect()
+}
+
+// both counts stop early once past `limit` since the line can't be a candidate any more
+fn count_differences_vertical(index: usize, square: &BitGrid, limit: usize) -> usize {
+    let mut differences = 0;
+    for (l, r) in (0..index).rev().zip(index..square.height()) {
+        differences += square.row_differences(l, r) as usize;
+        if differences > limit {
+            break;
         }
-        for idx in 1..i.len() {
-            if check_reflection_vertical(idx, &i) {
-                running_sum += idx * 100;
-                break;
-            }
+    }
+    differences
+}
+
+fn count_differences_horizontal(index: usize, square: &BitGrid, limit: usize) -> usize {
+    let mut differences = 0;
+    for (l, r) in (0..index).rev().zip(index..square.width()) {
+        differences += square.col_differences(l, r) as usize;
+        if differences > limit {
+            break;
         }
     }
-    println!("Total sum: {running_sum}");
-}
-
-fn check_one_diff_vertical(index: usize, square: &[Vec<char>]) -> bool {
-    (0..index)
-        .rev()
-        .zip(index..square.len())
-        .map(|(l, r)| {
-            square[l]
-                .iter()
-                .zip(&square[r])
-                .filter(|x| x.0 != x.1)
-                .count()
-        })
-        .sum::<usize>()
-        == 1
-}
-
-fn check_one_diff_horizontal(index: usize, square: &[Vec<char>]) -> bool {
-    (0..index)
-        .rev()
-        .zip(index..square[0].len())
-        .map(|(l, r)| {
-            square
-                .iter()
-                .map(|x| x[l])
-                .zip(square.iter().map(|x| x[r]))
-                .filter(|x| x.0 != x.1)
-                .count()
-        })
-        .sum::<usize>()
-        == 1
-}
-
-pub fn day13_p2() {
-    let input = get_day13_input("examples/day13_input.txt");
-    let mut running_sum = 0;
-    for i in input {
-        for idx in 1..i[0].len() {
-            if check_one_diff_horizontal(idx, &i) {
-                running_sum += idx;
-                break;
+    differences
+}
+
+/// Find every reflection line in the pattern with at most `max_differences` mismatched cells
+pub fn find_reflections(square: &BitGrid, max_differences: usize) -> Vec<Reflection> {
+    let horizontal = (1..square.width()).map(|idx| Reflection {
+        orientation: Orientation::Horizontal,
+        index: idx,
+        differences: count_differences_horizontal(idx, square, max_differences),
+    });
+    let vertical = (1..square.height()).map(|idx| Reflection {
+        orientation: Orientation::Vertical,
+        index: idx,
+        differences: count_differences_vertical(idx, square, max_differences),
+    });
+    horizontal
+        .chain(vertical)
+        .filter(|x| x.differences <= max_differences)
+        .collect()
+}
+
+/// Cells that differ from their mirror image across the reflection line, as (row, col)
+/// on the top/left side of the line
+pub fn smudge_cells(square: &BitGrid, reflection: &Reflection) -> Vec<(usize, usize)> {
+    let idx = reflection.index;
+    match reflection.orientation {
+        Orientation::Horizontal => (0..idx)
+            .rev()
+            .zip(idx..square.width())
+            .flat_map(|(l, r)| {
+                square
+                    .col_mismatches(l, r)
+                    .into_iter()
+                    .map(move |row| (row, l))
+            })
+            .collect(),
+        Orientation::Vertical => (0..idx)
+            .rev()
+            .zip(idx..square.height())
+            .flat_map(|(l, r)| {
+                square
+                    .row_mismatches(l, r)
+                    .into_iter()
+                    .map(move |col| (l, col))
+            })
+            .collect(),
+    }
+}
+
+/// A pattern's single-difference reflection line and the cell that has to be fixed for it
+#[derive(Debug, Clone)]
+pub struct Smudge {
+    /// Index of the pattern in the input
+    pub pattern: usize,
+    pub square: BitGrid,
+    pub reflection: Reflection,
+    /// (row, col) on the top/left side of the line
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Smudge {
+    /// The pattern with the smudged cells flipped and highlighted in red, or marked
+    /// with `*` when `colour` is off
+    pub fn render(&self, colour: bool) -> String {
+        let mut out = String::new();
+        for row_idx in 0..self.square.height() {
+            for col_idx in 0..self.square.width() {
+                let c = if self.square.get(row_idx, col_idx) {
+                    '#'
+                } else {
+                    '.'
+                };
+                if self.cells.contains(&(row_idx, col_idx)) {
+                    let fixed = if c == '#' { '.' } else { '#' };
+                    match colour {
+                        true => out.push_str(&format!("\x1b[1;31m{fixed}\x1b[0m")),
+                        false => out.push('*'),
+                    }
+                } else {
+                    out.push(c);
+                }
             }
+            out.push('\n');
         }
-        for idx in 1..i.len() {
-            if check_one_diff_vertical(idx, &i) {
-                running_sum += idx * 100;
-                break;
-            }
+        out
+    }
+}
+
+fn smudges_in(input: &[BitGrid]) -> Vec<Smudge> {
+    let mut smudges = Vec::new();
+    for (idx, i) in input.iter().enumerate() {
+        for reflection in find_reflections(i, 1)
+            .into_iter()
+            .filter(|x| x.differences == 1)
+        {
+            smudges.push(Smudge {
+                pattern: idx,
+                square: i.clone(),
+                cells: smudge_cells(i, &reflection),
+                reflection,
+            });
+        }
+    }
+    smudges
+}
+
+/// Every pattern's smudge, so callers can show where they are
+pub fn find_smudges(input: &str) -> Result<Vec<Smudge>, ParseError> {
+    Ok(smudges_in(&get_day13_input(input)?))
+}
+
+/// Sum the scores of every reflection line with exactly `smudges` differences
+fn summarise(input: &[BitGrid], smudges: usize) -> usize {
+    input
+        .iter()
+        .flat_map(|i| find_reflections(i, smudges))
+        .filter(|x| x.differences == smudges)
+        .map(|x| x.score())
+        .sum()
+}
+
+pub fn day13_p1(input: Vec<BitGrid>) -> SolveResult {
+    let running_sum = summarise(&input, 0);
+    Ok(running_sum.to_string())
+}
+
+pub fn day13_p2(input: Vec<BitGrid>) -> SolveResult {
+    // finding them again copies every pattern, so only bother when they'll be shown
+    if log_enabled!(Level::Debug) {
+        for smudge in smudges_in(&input) {
+            debug!(
+                "Pattern {}: smudge at {:?} ({:?})",
+                smudge.pattern, smudge.cells, smudge.reflection
+            );
         }
     }
-    println!("Total sum: {running_sum}");
+    let running_sum = summarise(&input, 1);
+    Ok(running_sum.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day13_example.txt");
+
+    #[test]
+    fn plain_render_marks_the_smudge() {
+        let smudges = find_smudges(EXAMPLE).unwrap();
+        assert_eq!(smudges.len(), 2);
+        let plain = smudges[0].render(false);
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(plain.lines().next(), Some("*.##..##."));
+        assert!(smudges[0].render(true).starts_with("\x1b[1;31m.\x1b[0m"));
+    }
 }
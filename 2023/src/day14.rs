@@ -1,208 +1,272 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-    hash::Hash,
-};
-
-#[derive(Debug, Eq, PartialEq, Clone, Hash)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
+use crate::cycle::{brent, try_brent_at_start};
+use crate::grid::Grid;
+use crate::parse::{self, ParseError};
+use crate::point::{Direction, ParseDirectionError};
+use aoc_core::cancel::{CancelToken, Cancelled};
+use aoc_core::progress::Progress;
+use aoc_core::SolveResult;
+use log::debug;
+use std::{fmt, hash::Hash, str::FromStr, thread, time::Duration};
+
+/// Sequence of tilts applied in order, written as a string of direction letters like "NWSE"
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TiltProgram(pub Vec<Direction>);
+
+impl FromStr for TiltProgram {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(Direction::try_from)
+            .collect::<Result<Vec<Direction>, _>>()
+            .map(TiltProgram)
+    }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Hash)]
-enum Stone {
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub(crate) enum Stone {
     Round,
     Cube,
     Space,
 }
 
-impl From<char> for Stone {
-    fn from(value: char) -> Self {
-        match value {
-            'O' => Stone::Round,
-            '#' => Stone::Cube,
-            '.' => Stone::Space,
-            _ => panic!("Can't make Stone type from char {value}"),
-        }
-    }
-}
+impl TryFrom<char> for Stone {
+    type Error = ParseError;
 
-fn get_day14_input(path: &str) -> Vec<Vec<Stone>> {
-    let input_str = fs::read_to_string(path).unwrap();
-    input_str
-        .lines()
-        .map(|l| l.chars().map(Stone::from).collect())
-        .collect()
-}
-
-// Recursive rolling function to operate column-wise
-fn roll_column(mut column: Vec<Stone>) -> Vec<Stone> {
-    let mut num_changes = 0;
-
-    // pass down over the length of the column
-    for i in 1..column.len() {
-        // only operate on round stones
-        if column[i] == Stone::Round {
-            let mut new_index = i.clone();
-            // go backwards up the column finding positions
-            for newpos in (0..i).rev() {
-                // if it's a gap then add this as the latest candidate
-                if column[newpos] == Stone::Space {
-                    new_index = newpos;
-                // if full then stop
-                } else {
-                    break;
-                }
-            }
-            // move stone if we found a higher up position
-            if new_index != i {
-                num_changes += 1;
-                column[new_index] = Stone::Round;
-                column[i] = Stone::Space;
-            }
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'O' => Ok(Stone::Round),
+            '#' => Ok(Stone::Cube),
+            '.' => Ok(Stone::Space),
+            _ => Err(ParseError::invalid(&value.to_string(), "a stone")),
         }
     }
-    // convergence check: if we didn't make any changes then stop
-    if num_changes != 0 {
-        roll_column(column)
-    } else {
-        column
-    }
 }
 
-fn column_load(column: &Vec<Stone>) -> usize {
-    let mut total_load = 0;
-    for (idx, i) in column.iter().enumerate() {
-        match i {
-            Stone::Round => total_load += column.len() - idx,
-            _ => (),
-        }
+impl fmt::Display for Stone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Stone::Round => 'O',
+            Stone::Cube => '#',
+            Stone::Space => '.',
+        };
+        write!(f, "{symbol}")
     }
-    total_load
 }
 
-pub fn day14_p1() {
-    let input = get_day14_input("examples/day14_input.txt");
-
-    let mut cache = HashMap::new();
-    let mut total_load = 0;
+pub(crate) type Platform = Grid<Stone>;
 
-    for col in 0..input[0].len() {
-        let mut col_vector: Vec<Stone> = input.iter().map(|x| x[col].clone()).collect();
-        col_vector = roll_column_memo(col_vector, &mut cache);
-        total_load += column_load(&col_vector);
-    }
+/// Total load on the given edge: each round stone counts its distance from the far edge
+fn load(platform: &Platform, edge: &Direction) -> usize {
+    platform
+        .iter()
+        .filter(|(_, x)| **x == Stone::Round)
+        .map(|(point, _)| match edge {
+            Direction::North => platform.height() - point.row,
+            Direction::South => point.row + 1,
+            Direction::West => platform.width() - point.col,
+            Direction::East => point.col + 1,
+        })
+        .sum()
+}
 
-    println!("Total load: {:#?}", total_load);
+pub(crate) fn get_day14_input(input: &str) -> Result<Platform, ParseError> {
+    parse::grid(input)
 }
 
-// memoised version
-fn roll_column_memo(column: Vec<Stone>, cache: &mut HashMap<Vec<Stone>, Vec<Stone>>) -> Vec<Stone> {
-    match cache.get(&column) {
-        Some(x) => x.to_vec(),
-        None => {
-            let out = roll_column(column.clone());
-            cache.insert(column, out.clone());
-            out
+// Single pass along one lane of the buffer, starting from the edge stones roll towards.
+// Each round stone drops into the first free slot after the last cube (or the edge).
+fn roll_lane(cells: &mut [Stone], start: usize, step: isize, len: usize) {
+    let position = |i: usize| start.wrapping_add_signed(i as isize * step);
+    let mut free = 0;
+    for i in 0..len {
+        match cells[position(i)] {
+            Stone::Cube => free = i + 1,
+            Stone::Round => {
+                if free != i {
+                    cells[position(i)] = Stone::Space;
+                    cells[position(free)] = Stone::Round;
+                }
+                free += 1;
+            }
+            Stone::Space => (),
         }
     }
 }
 
-// Recursive rolling function to operate column-wise
-fn roll_box(mut square: Vec<Vec<Stone>>, direction: Direction) -> Vec<Vec<Stone>> {
+// Tilt the whole platform in place, one lane per column (north/south) or row (east/west)
+pub(crate) fn roll_box(square: &mut Platform, direction: &Direction) {
+    let (width, height) = (square.width(), square.height());
     match direction {
         Direction::North => {
-            for col in 0..square[0].len() {
-                let mut column: Vec<Stone> = square.iter().map(|x| x[col].clone()).collect();
-                column = roll_column(column);
-                for (idx, i) in column.into_iter().enumerate() {
-                    square[idx][col] = i;
-                }
+            for col in 0..width {
+                roll_lane(square.cells_mut(), col, width as isize, height);
             }
         }
         Direction::South => {
-            for col in 0..square[0].len() {
-                let mut column: Vec<Stone> = square.iter().map(|x| x[col].clone()).collect();
-                // reverse before and after to get south for 'free' using roll column
-                column.reverse();
-                column = roll_column(column);
-                column.reverse();
-                for (idx, i) in column.into_iter().enumerate() {
-                    square[idx][col] = i;
-                }
+            for col in 0..width {
+                roll_lane(
+                    square.cells_mut(),
+                    (height - 1) * width + col,
+                    -(width as isize),
+                    height,
+                );
             }
         }
         Direction::West => {
-            for row in 0..square.len() {
-                let mut row_vec: Vec<Stone> = square[row].clone();
-                row_vec = roll_column(row_vec);
-                square[row] = row_vec;
+            for row in 0..height {
+                roll_lane(square.cells_mut(), row * width, 1, width);
             }
         }
         Direction::East => {
-            for row in 0..square.len() {
-                let mut row_vec: Vec<Stone> = square[row].clone();
-                // reverse before and after to get east for 'free' using roll column
-                row_vec.reverse();
-                row_vec = roll_column(row_vec);
-                row_vec.reverse();
-                square[row] = row_vec;
+            for row in 0..height {
+                roll_lane(square.cells_mut(), row * width + width - 1, -1, width);
             }
         }
     }
-    square
 }
 
-pub fn day14_p2() {
-    let mut input = get_day14_input("examples/day14_input.txt");
-
-    let mut seen_set: HashSet<Vec<Vec<Stone>>> = HashSet::new();
-    let mut seen_vec: Vec<Vec<Vec<Stone>>> = vec![];
-    seen_set.insert(input.clone());
-    seen_vec.push(input.clone());
-    let mut print;
-    let mut iter = 0;
-    loop {
-        if iter % 1 == 0 {
-            print = true;
-        } else {
-            print = false;
-        }
-        if print {
-            println!("Running {iter:>10}");
-        }
+pub(crate) fn day14_p1(mut input: Platform) -> SolveResult {
+    roll_box(&mut input, &Direction::North);
+    Ok(load(&input, &Direction::North).to_string())
+}
 
-        // do cycle
-        input = roll_box(input, Direction::North);
-        input = roll_box(input, Direction::West);
-        input = roll_box(input, Direction::South);
-        input = roll_box(input, Direction::East);
+fn run_program(square: &mut Platform, program: &TiltProgram) {
+    for direction in &program.0 {
+        roll_box(square, direction);
+    }
+}
+
+pub(crate) fn day14_p2(
+    input: Platform,
+    program: &TiltProgram,
+    cycles: usize,
+    edge: &Direction,
+    cancel: &CancelToken,
+    progress: &Progress,
+) -> SolveResult {
+    // a program that never settles into a loop would spin forever without the check
+    progress.phase("finding cycle", None);
+    let (cycle, loop_start) = try_brent_at_start(&input, |x| {
+        cancel.check()?;
+        run_program(x, program);
+        progress.advance(1);
+        Ok::<(), Cancelled>(())
+    })?;
+    debug!("Cycle start: {}, length: {}", cycle.start, cycle.length);
+
+    // skip the whole loops and only run what's left over, carrying on from the loop
+    // start unless we stop before reaching it
+    let (mut board, remaining) = match cycles.checked_sub(cycle.start) {
+        Some(past_start) => (loop_start, past_start % cycle.length),
+        None => (input, cycles),
+    };
+    progress.phase("running leftover cycles", Some(remaining as u64));
+    for _ in 0..remaining {
+        cancel.check()?;
+        run_program(&mut board, program);
+        progress.advance(1);
+    }
+
+    Ok(load(&board, edge).to_string())
+}
 
-        // add to seen cycles or break if already seen
-        if !seen_set.insert(input.clone()) {
-            break;
+/// Redraw the platform in the terminal after every tilt, up to the end of the first full loop
+pub fn day14_replay(
+    input: &str,
+    program: &TiltProgram,
+    cycles: usize,
+    edge: &Direction,
+    frame_delay: Duration,
+) -> Result<(), ParseError> {
+    let mut input = get_day14_input(input)?;
+    let cycle = brent(&input, |x| run_program(x, program));
+    // after one full loop it only repeats itself
+    let replay_cycles = cycles.min(cycle.start + cycle.length);
+
+    for iter in 1..=replay_cycles {
+        for (tilt, direction) in program.0.iter().enumerate() {
+            roll_box(&mut input, direction);
+            // clear screen and move the cursor home before each frame
+            print!("\x1b[2J\x1b[H");
+            println!(
+                "Cycle {iter:>10}/{replay_cycles}  tilt {direction:?}  {edge:?} load: {}",
+                load(&input, edge)
+            );
+            // the board after `cycle.start` cycles is the first one that comes round again,
+            // so it's only reached once the last tilt of that cycle is done
+            if iter == cycle.start && tilt == program.0.len() - 1 {
+                println!(
+                    "\x1b[1;31mCycle starts here, length {}\x1b[0m",
+                    cycle.length
+                );
+            } else if iter > cycle.start {
+                println!(
+                    "\x1b[1;33mIn cycle: started after {} cycles, length {}\x1b[0m",
+                    cycle.start, cycle.length
+                );
+            } else {
+                println!();
+            }
+            println!("{input}");
+            thread::sleep(frame_delay);
         }
-        // add to vec to store index positions
-        seen_vec.push(input.clone());
-        iter += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn count(platform: &Platform, stone: Stone) -> usize {
+        platform.cells().iter().filter(|x| **x == stone).count()
     }
 
-    let cycles = 1_000_000_000;
-    // save the first time we saw the end point of the cycle
-    let first_cycle_idx = seen_vec.iter().position(|x| x == &input).unwrap();
-    println!("First pos: {}", first_cycle_idx);
-    // end position is number of steps into the cycle, plus start point of cycle
-    let final_grid = seen_vec
-        [(cycles - first_cycle_idx) % (seen_vec.len() - first_cycle_idx) + first_cycle_idx]
-        .clone();
-    let mut total_load = 0;
-    for col in 0..input[0].len() {
-        let col_vector: Vec<Stone> = final_grid.iter().map(|x| x[col].clone()).collect();
-        total_load += column_load(&col_vector);
+    #[test]
+    fn skipping_the_loop_matches_spinning_every_cycle() {
+        let input = get_day14_input(include_str!("../examples/day14_example.txt")).unwrap();
+        let program: TiltProgram = "NWSE".parse().unwrap();
+        let mut spun = input.clone();
+        // the example loops after 3 cycles, every 7
+        for cycles in 0..25 {
+            let answer = day14_p2(
+                input.clone(),
+                &program,
+                cycles,
+                &Direction::North,
+                &CancelToken::new(),
+                &Progress::new(),
+            )
+            .unwrap();
+            assert_eq!(
+                answer,
+                load(&spun, &Direction::North).to_string(),
+                "{cycles}"
+            );
+            run_program(&mut spun, &program);
+        }
     }
 
-    println!("Total load: {:#?}", total_load);
+    proptest! {
+        #[test]
+        fn tilting_keeps_every_round_stone(
+            width in 1usize..12,
+            cells in prop::collection::vec(prop::sample::select(vec!['O', '#', '.']), 1..144),
+            program in "[NESW]{1,8}",
+        ) {
+            let height = cells.len() / width;
+            prop_assume!(height > 0);
+            let mut platform: Platform =
+                Grid::new(width, height, cells[..width * height].iter().map(|&x| Stone::try_from(x).unwrap()).collect());
+            let before = platform.clone();
+            run_program(&mut platform, &program.parse().unwrap());
+            prop_assert_eq!(count(&platform, Stone::Round), count(&before, Stone::Round));
+            // cubes never move
+            for (point, stone) in before.iter() {
+                prop_assert_eq!(*stone == Stone::Cube, platform[point] == Stone::Cube);
+            }
+        }
+    }
 }
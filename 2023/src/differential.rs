@@ -0,0 +1,412 @@
+use crate::day05::{
+    lowest_location_by_ranges, lowest_location_per_seed, read_day5_input, SeedMapping, MAP_NAMES,
+};
+use crate::day12::{count_groups, count_groups_memo, RecordType};
+use crate::day14::{roll_box, Platform, Stone};
+use crate::generate;
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::rng::Rng;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+/// An input that a slow but obviously right implementation and an optimised one
+/// should always agree on
+trait Subject: Clone + fmt::Display {
+    fn generate(rng: &mut Rng) -> Self;
+    fn naive(&self) -> String;
+    fn optimised(&self) -> String;
+    /// Strictly smaller variations to try when shrinking a divergence
+    fn smaller(&self) -> Vec<Self>;
+}
+
+/// The first generated input the two implementations disagreed on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Index of the generated case, so it can be found again from the seed
+    pub case: usize,
+    pub input: String,
+    /// Smallest input found by shrinking that still disagrees
+    pub shrunk: String,
+    pub naive: String,
+    pub optimised: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "case {}:", self.case)?;
+        writeln!(f, "{}", self.input.trim_end())?;
+        writeln!(f, "shrunk to:")?;
+        writeln!(f, "{}", self.shrunk.trim_end())?;
+        writeln!(f, "naive: {}", self.naive)?;
+        write!(f, "optimised: {}", self.optimised)
+    }
+}
+
+fn diverges<T: Subject>(case: &T) -> bool {
+    case.naive() != case.optimised()
+}
+
+// Greedily take the first smaller case that still disagrees until none do
+fn shrink<T: Subject>(mut case: T) -> T {
+    while let Some(smaller) = case.smaller().into_iter().find(diverges) {
+        case = smaller;
+    }
+    case
+}
+
+fn check<T: Subject>(seed: u64, cases: usize) -> Result<(), Divergence> {
+    let mut rng = Rng::new(seed);
+    for case in 0..cases {
+        let input = T::generate(&mut rng);
+        if diverges(&input) {
+            let shrunk = shrink(input.clone());
+            return Err(Divergence {
+                case,
+                input: input.to_string(),
+                shrunk: shrunk.to_string(),
+                naive: shrunk.naive(),
+                optimised: shrunk.optimised(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Two implementations of the same thing, checked against each other
+pub struct Pair {
+    pub name: &'static str,
+    pub check: fn(seed: u64, cases: usize) -> Result<(), Divergence>,
+}
+
+pub const PAIRS: &[Pair] = &[
+    Pair {
+        name: "day 12 count_groups vs count_groups_memo",
+        check: |seed, cases| check::<SpringRow>(seed, cases),
+    },
+    Pair {
+        name: "day 14 naive tilt vs roll_box",
+        check: |seed, cases| check::<Tilt>(seed, cases),
+    },
+    Pair {
+        name: "day 5 per-seed vs range mapping",
+        check: |seed, cases| check::<SeedRanges>(seed, cases),
+    },
+];
+
+#[derive(Debug, Clone)]
+struct SpringRow(Vec<RecordType>, Vec<usize>);
+
+impl fmt::Display for SpringRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.0 {
+            let c = match record {
+                RecordType::Damaged => '#',
+                RecordType::Operational => '.',
+                RecordType::Unknown => '?',
+            };
+            write!(f, "{c}")?;
+        }
+        let groups: Vec<String> = self.1.iter().map(|x| x.to_string()).collect();
+        write!(f, " {}", groups.join(","))
+    }
+}
+
+impl Subject for SpringRow {
+    fn generate(rng: &mut Rng) -> Self {
+        let line = generate::day12(rng, 1);
+        let (records, groups) = line.trim().split_once(' ').unwrap();
+        SpringRow(
+            records
+                .chars()
+                .map(|x| x.to_string().parse().unwrap())
+                .collect(),
+            groups.split(',').map(|x| x.parse().unwrap()).collect(),
+        )
+    }
+
+    fn naive(&self) -> String {
+        count_groups(&self.0, &self.1).to_string()
+    }
+
+    fn optimised(&self) -> String {
+        count_groups_memo(&self.0, &self.1, &mut HashMap::new()).to_string()
+    }
+
+    fn smaller(&self) -> Vec<Self> {
+        let mut smaller = Vec::new();
+        for i in 0..self.0.len() {
+            let mut records = self.0.clone();
+            records.remove(i);
+            smaller.push(SpringRow(records, self.1.clone()));
+            if self.0[i] == RecordType::Unknown {
+                for known in [RecordType::Operational, RecordType::Damaged] {
+                    let mut records = self.0.clone();
+                    records[i] = known;
+                    smaller.push(SpringRow(records, self.1.clone()));
+                }
+            }
+        }
+        let mut fewer_groups = Vec::new();
+        for i in 0..self.1.len() {
+            let mut groups = self.1.clone();
+            groups.remove(i);
+            fewer_groups.push(groups);
+            if self.1[i] > 1 {
+                let mut groups = self.1.clone();
+                groups[i] -= 1;
+                fewer_groups.push(groups);
+            }
+        }
+        // dropping a spring on its own usually breaks the row, so also try dropping
+        // it along with part of a group
+        for groups in fewer_groups {
+            smaller.push(SpringRow(self.0.clone(), groups.clone()));
+            for i in 0..self.0.len() {
+                let mut records = self.0.clone();
+                records.remove(i);
+                smaller.push(SpringRow(records, groups.clone()));
+            }
+        }
+        smaller
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Tilt(Platform);
+
+impl fmt::Display for Tilt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Nudge round stones one cell at a time until none of them can move
+fn tilt_naive(platform: &mut Platform, direction: Direction) {
+    loop {
+        let mut moved = false;
+        let points: Vec<Point> = platform.iter().map(|(point, _)| point).collect();
+        for point in points {
+            if platform[point] != Stone::Round {
+                continue;
+            }
+            if let Some(next) = platform.step(&point, direction) {
+                if platform[next] == Stone::Space {
+                    platform[next] = Stone::Round;
+                    platform[point] = Stone::Space;
+                    moved = true;
+                }
+            }
+        }
+        if !moved {
+            return;
+        }
+    }
+}
+
+impl Tilt {
+    fn each_direction(&self, tilt: impl Fn(&mut Platform, Direction)) -> String {
+        Direction::ALL
+            .iter()
+            .map(|&direction| {
+                let mut platform = self.0.clone();
+                tilt(&mut platform, direction);
+                format!("{direction:?}:\n{platform}")
+            })
+            .collect()
+    }
+}
+
+impl Subject for Tilt {
+    fn generate(rng: &mut Rng) -> Self {
+        let size = 1 + rng.below(10);
+        Tilt(Grid::parse(&generate::day14(rng, size)).unwrap())
+    }
+
+    fn naive(&self) -> String {
+        self.each_direction(tilt_naive)
+    }
+
+    fn optimised(&self) -> String {
+        self.each_direction(|platform, direction| roll_box(platform, &direction))
+    }
+
+    fn smaller(&self) -> Vec<Self> {
+        let (width, height) = (self.0.width(), self.0.height());
+        let rows: Vec<Vec<Stone>> = self.0.rows().map(|x| x.to_vec()).collect();
+        let mut smaller = Vec::new();
+        if height > 1 {
+            for skip in 0..height {
+                let mut rows = rows.clone();
+                rows.remove(skip);
+                smaller.push(Tilt(Grid::from_rows(rows)));
+            }
+        }
+        if width > 1 {
+            for skip in 0..width {
+                let rows = rows
+                    .iter()
+                    .map(|row| [&row[..skip], &row[skip + 1..]].concat())
+                    .collect();
+                smaller.push(Tilt(Grid::from_rows(rows)));
+            }
+        }
+        for (point, stone) in self.0.iter() {
+            if *stone != Stone::Space {
+                let mut platform = self.0.clone();
+                platform[point] = Stone::Space;
+                smaller.push(Tilt(platform));
+            }
+        }
+        smaller
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SeedRanges {
+    seeds: Vec<Range<u64>>,
+    stages: Vec<Vec<SeedMapping>>,
+}
+
+impl fmt::Display for SeedRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seeds:")?;
+        for x in &self.seeds {
+            write!(f, " {} {}", x.start, x.end - x.start)?;
+        }
+        writeln!(f)?;
+        for (name, maps) in MAP_NAMES.iter().zip(&self.stages) {
+            writeln!(f, "\n{name} map:")?;
+            for x in maps {
+                writeln!(f, "{} {} {}", x.dest_start, x.src_start, x.length)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SeedRanges {
+    fn stages(&self) -> Vec<&Vec<SeedMapping>> {
+        self.stages.iter().collect()
+    }
+}
+
+impl Subject for SeedRanges {
+    fn generate(rng: &mut Rng) -> Self {
+        let size = 1 + rng.below(3);
+        let (seeds, s1, s2, s3, s4, s5, s6, s7) =
+            read_day5_input(&generate::day5(rng, size)).unwrap();
+        SeedRanges {
+            seeds: seeds.chunks_exact(2).map(|x| x[0]..x[0] + x[1]).collect(),
+            stages: vec![s1, s2, s3, s4, s5, s6, s7],
+        }
+    }
+
+    fn naive(&self) -> String {
+        format!(
+            "{:?}",
+            lowest_location_per_seed(&self.seeds, &self.stages())
+        )
+    }
+
+    fn optimised(&self) -> String {
+        format!(
+            "{:?}",
+            lowest_location_by_ranges(&self.seeds, &self.stages())
+        )
+    }
+
+    fn smaller(&self) -> Vec<Self> {
+        let mut smaller = Vec::new();
+        for i in 0..self.seeds.len() {
+            let mut seeds = self.seeds.clone();
+            seeds.remove(i);
+            smaller.push(SeedRanges {
+                seeds,
+                stages: self.stages.clone(),
+            });
+            let range = &self.seeds[i];
+            if range.end - range.start > 1 {
+                let half = (range.end - range.start) / 2;
+                for part in [
+                    range.start..range.start + half,
+                    range.start + half..range.end,
+                ] {
+                    let mut seeds = self.seeds.clone();
+                    seeds[i] = part;
+                    smaller.push(SeedRanges {
+                        seeds,
+                        stages: self.stages.clone(),
+                    });
+                }
+            }
+        }
+        for (stage, maps) in self.stages.iter().enumerate() {
+            for i in 0..maps.len() {
+                let mut stages = self.stages.clone();
+                stages[stage].remove(i);
+                smaller.push(SeedRanges {
+                    seeds: self.seeds.clone(),
+                    stages,
+                });
+            }
+        }
+        smaller
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_agree_on_generated_inputs() {
+        for pair in PAIRS {
+            if let Err(e) = (pair.check)(2023, 200) {
+                panic!("{e}");
+            }
+        }
+    }
+
+    // count_groups with a made up bug, to check shrinking gets down to a tiny row
+    #[derive(Debug, Clone)]
+    struct Buggy(SpringRow);
+
+    impl fmt::Display for Buggy {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Subject for Buggy {
+        fn generate(rng: &mut Rng) -> Self {
+            Buggy(SpringRow::generate(rng))
+        }
+
+        fn naive(&self) -> String {
+            self.0.naive()
+        }
+
+        fn optimised(&self) -> String {
+            match self.0 .0.contains(&RecordType::Unknown) {
+                true => "0".to_string(),
+                false => self.0.optimised(),
+            }
+        }
+
+        fn smaller(&self) -> Vec<Self> {
+            self.0.smaller().into_iter().map(Buggy).collect()
+        }
+    }
+
+    #[test]
+    fn divergence_is_shrunk() {
+        let divergence = check::<Buggy>(1, 100).unwrap_err();
+        assert_eq!(divergence.shrunk, "? ");
+        assert_eq!(
+            (divergence.naive.as_str(), divergence.optimised.as_str()),
+            ("1", "0")
+        );
+    }
+}
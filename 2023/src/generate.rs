@@ -0,0 +1,684 @@
+use crate::bitgrid::BitGrid;
+use crate::day05::MAP_NAMES;
+use crate::day13::find_reflections;
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::rng::Rng;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Random puzzle input for a day, the same for the same seed and size
+pub struct Generator {
+    pub day: u8,
+    /// Size that gives an input about as big as the real puzzle's
+    pub default_size: usize,
+    pub generate: fn(&mut Rng, usize) -> String,
+}
+
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        default_size: 1000,
+        generate: day1,
+    },
+    Generator {
+        day: 2,
+        default_size: 100,
+        generate: day2,
+    },
+    Generator {
+        day: 3,
+        default_size: 140,
+        generate: day3,
+    },
+    Generator {
+        day: 4,
+        default_size: 200,
+        generate: day4,
+    },
+    Generator {
+        day: 5,
+        default_size: 10,
+        generate: day5,
+    },
+    Generator {
+        day: 6,
+        default_size: 4,
+        generate: day6,
+    },
+    Generator {
+        day: 7,
+        default_size: 1000,
+        generate: day7,
+    },
+    Generator {
+        day: 8,
+        default_size: 280,
+        generate: day8,
+    },
+    Generator {
+        day: 9,
+        default_size: 200,
+        generate: day9,
+    },
+    Generator {
+        day: 10,
+        default_size: 140,
+        generate: day10,
+    },
+    Generator {
+        day: 11,
+        default_size: 140,
+        generate: day11,
+    },
+    Generator {
+        day: 12,
+        default_size: 1000,
+        generate: day12,
+    },
+    Generator {
+        day: 13,
+        default_size: 100,
+        generate: day13,
+    },
+    Generator {
+        day: 14,
+        default_size: 100,
+        generate: day14,
+    },
+];
+
+pub fn find_generator(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|x| x.day == day)
+}
+
+/// Input for a day from a seed, or None if the day has no generator
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    find_generator(day).map(|x| (x.generate)(&mut Rng::new(seed), size))
+}
+
+const DIGIT_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// `size` lines of letters, digits and spelled out digits, each with at least one real digit
+pub fn day1(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        for _ in 0..rng.range(2..8) {
+            match rng.below(4) {
+                0 => line.push_str(DIGIT_WORDS[rng.below(DIGIT_WORDS.len())]),
+                1 => line.push(char::from(b'1' + rng.below(9) as u8)),
+                _ => line.push(char::from(b'a' + rng.below(26) as u8)),
+            }
+        }
+        let digit = char::from(b'1' + rng.below(9) as u8);
+        line.insert(rng.below(line.len() + 1), digit);
+        writeln!(out, "{line}").unwrap();
+    }
+    out
+}
+
+/// `size` games of up to six rounds, each round showing each colour at most once
+pub fn day2(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for game in 1..=size {
+        let rounds: Vec<String> = (0..rng.range(1..7))
+            .map(|_| {
+                let mut colours = ["red", "green", "blue"];
+                rng.shuffle(&mut colours);
+                let shown = rng.range(1..4) as usize;
+                colours[..shown]
+                    .iter()
+                    .map(|colour| format!("{} {colour}", rng.range(1..21)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect();
+        writeln!(out, "Game {game}: {}", rounds.join("; ")).unwrap();
+    }
+    out
+}
+
+/// A `size` by `size` schematic of part numbers and symbols
+pub fn day3(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(3);
+    let symbols = ['*', '#', '+', '$', '/', '@', '%', '=', '-', '&'];
+    let mut cells = vec!['.'; size * size];
+    for row in cells.chunks_mut(size) {
+        let mut col = 0;
+        while col < size {
+            if rng.chance(0.1) {
+                // numbers need a non-digit after them so they don't run together
+                let len = (rng.range(1..4) as usize).min(size - col);
+                for cell in &mut row[col..col + len] {
+                    *cell = char::from(b'0' + rng.below(10) as u8);
+                }
+                col += len + 1;
+            } else {
+                if rng.chance(0.08) {
+                    row[col] = *rng.pick(&symbols);
+                }
+                col += 1;
+            }
+        }
+    }
+    Grid::new(size, size, cells).to_string()
+}
+
+/// `size` scratchcards of 10 winning numbers and 25 of ours. Wins never reach past
+/// the last card and the total number of cards stays around what real inputs give.
+pub fn day4(rng: &mut Rng, size: usize) -> String {
+    const LIMIT: u64 = 10_000_000;
+    let mut copies = vec![1u64; size];
+    let mut total = size as u64;
+    let mut out = String::new();
+    for card in 0..size {
+        let mut numbers: Vec<u32> = (1..100).collect();
+        rng.shuffle(&mut numbers);
+        let (winning, rest) = numbers.split_at(10);
+        let mut wins = [0, 0, 1, 1, 2, 3, 4, 5, 10][rng.below(9)].min(size - card - 1);
+        if total + copies[card] * wins as u64 > LIMIT {
+            wins = 0;
+        }
+        let won = copies[card];
+        for later in &mut copies[card + 1..=card + wins] {
+            *later += won;
+        }
+        total += won * wins as u64;
+
+        let mut ours: Vec<u32> = winning[..wins]
+            .iter()
+            .chain(&rest[..25 - wins])
+            .copied()
+            .collect();
+        rng.shuffle(&mut ours);
+        let show = |x: &[u32]| {
+            x.iter()
+                .map(|x| format!("{x:>2}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        writeln!(
+            out,
+            "Card {:>3}: {} | {}",
+            card + 1,
+            show(winning),
+            show(&ours)
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// An almanac of `size` seed ranges and maps of about `size` lines each. Every map
+/// shuffles the pieces of [0, span) around, so each one is a bijection.
+pub fn day5(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let span = size as u64 * 100_000;
+    let mut out = String::from("seeds:");
+    for _ in 0..size {
+        let length = rng.range(1..10_000);
+        write!(out, " {} {length}", rng.range(0..span - length)).unwrap();
+    }
+    out.push('\n');
+
+    for name in MAP_NAMES {
+        let mut cuts: Vec<u64> = (0..size + 1).map(|_| rng.range(1..span)).collect();
+        cuts.extend([0, span]);
+        cuts.sort();
+        cuts.dedup();
+        let pieces: Vec<(u64, u64)> = cuts.windows(2).map(|x| (x[0], x[1] - x[0])).collect();
+        let mut order: Vec<usize> = (0..pieces.len()).collect();
+        rng.shuffle(&mut order);
+
+        // lay the pieces end to end in shuffled order to get their destinations
+        let mut lines = Vec::new();
+        let mut dest = 0;
+        for &i in &order {
+            let (src, length) = pieces[i];
+            if dest != src {
+                lines.push(format!("{dest} {src} {length}"));
+            }
+            dest += length;
+        }
+        rng.shuffle(&mut lines);
+        write!(out, "\n{name} map:\n").unwrap();
+        for line in lines {
+            writeln!(out, "{line}").unwrap();
+        }
+    }
+    out
+}
+
+/// `size` races, capped at four so the joined up part 2 race stays brute-forceable
+pub fn day6(rng: &mut Rng, size: usize) -> String {
+    let races: Vec<(u64, u64)> = (0..size.clamp(1, 4))
+        .map(|_| {
+            let time = rng.range(10..100);
+            // beatable, but not by every button press
+            let best = (time / 2) * (time - time / 2);
+            (time, rng.range(time..best))
+        })
+        .collect();
+    let show = |x: Vec<u64>| x.iter().map(|x| format!("{x:>6}")).collect::<String>();
+    format!(
+        "Time:    {}\nDistance:{}\n",
+        show(races.iter().map(|x| x.0).collect()),
+        show(races.iter().map(|x| x.1).collect())
+    )
+}
+
+/// `size` hands with bids
+pub fn day7(rng: &mut Rng, size: usize) -> String {
+    let cards: Vec<char> = "23456789TJQKA".chars().collect();
+    let mut out = String::new();
+    for _ in 0..size {
+        let hand: String = (0..5).map(|_| *rng.pick(&cards)).collect();
+        writeln!(out, "{hand} {}", rng.range(1..1001)).unwrap();
+    }
+    out
+}
+
+/// Instructions of up to `size` steps, with ghosts that each walk a loop whose
+/// length is a multiple of the instructions, finishing on their Z node exactly
+/// once per lap. That's the shape part 2's lcm relies on.
+pub fn day8(rng: &mut Rng, size: usize) -> String {
+    let length = 2 + rng.below(size.clamp(1, 300));
+    let instructions: Vec<usize> = (0..length).map(|_| rng.below(2)).collect();
+    let ghosts = 1 + rng.below(6);
+
+    // names for every node, keeping ...A and ...Z for the starts and ends
+    let letter = |x: usize| char::from(b'A' + x as u8);
+    let mut middles: Vec<String> = (0..26 * 26 * 26)
+        .map(|x| {
+            [letter(x / 676), letter(x / 26 % 26), letter(x % 26)]
+                .iter()
+                .collect()
+        })
+        .filter(|x: &String| !x.ends_with('A') && !x.ends_with('Z'))
+        .collect();
+    rng.shuffle(&mut middles);
+    // AA and ZZ belong to the ghost part 1 follows from AAA to ZZZ
+    let mut stems: Vec<String> = (1..26 * 26 - 1)
+        .map(|x| [letter(x / 26), letter(x % 26)].iter().collect())
+        .collect();
+    rng.shuffle(&mut stems);
+
+    let mut chains: Vec<Vec<String>> = Vec::new();
+    for (ghost, stem) in stems.iter().take(ghosts).enumerate() {
+        let (start, end) = match ghost {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (format!("{stem}A"), format!("{stem}Z")),
+        };
+        let laps = 1 + rng.below(7);
+        let mut chain = vec![start];
+        chain.extend(middles.drain(..length * laps - 1));
+        chain.push(end);
+        chains.push(chain);
+    }
+    let all: Vec<&String> = chains.iter().flatten().collect();
+
+    let mut nodes = Vec::new();
+    for chain in &chains {
+        // position i is always reached on instruction i % length, so only that side matters
+        for (i, node) in chain.iter().enumerate() {
+            let next = if i + 1 < chain.len() {
+                &chain[i + 1]
+            } else {
+                &chain[1]
+            };
+            let mut sides = [*rng.pick(&all), *rng.pick(&all)];
+            sides[instructions[i % length]] = next;
+            nodes.push(format!("{node} = ({}, {})", sides[0], sides[1]));
+        }
+    }
+    rng.shuffle(&mut nodes);
+    let instructions: String = instructions.iter().map(|&x| ['L', 'R'][x]).collect();
+    format!("{instructions}\n\n{}\n", nodes.join("\n"))
+}
+
+/// `size` sequences, each a polynomial of degree at most six so the differences
+/// always bottom out in zeroes
+pub fn day9(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let len = rng.range(8..22) as usize;
+        let degree = rng.below(7);
+        // leading entries of each difference row, summed back up into the sequence
+        let mut row: Vec<i64> = (0..=degree).map(|_| rng.range(0..21) as i64 - 10).collect();
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len {
+            values.push(row[0]);
+            for i in 0..degree {
+                row[i] += row[i + 1];
+            }
+        }
+        let line: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+        writeln!(out, "{}", line.join(" ")).unwrap();
+    }
+    out
+}
+
+// Whether adding `cell` to the region would leave two cells touching only at a corner,
+// which would make the boundary cross itself
+fn pinches(region: &Grid<bool>, row: usize, col: usize) -> bool {
+    let inside = |r: isize, c: isize| {
+        (r == row as isize && c == col as isize)
+            || (r >= 0
+                && c >= 0
+                && region
+                    .get(&Point::new(r as usize, c as usize))
+                    .copied()
+                    .unwrap_or(false))
+    };
+    let (row, col) = (row as isize, col as isize);
+    [(-1, -1), (-1, 0), (0, -1), (0, 0)].iter().any(|(dr, dc)| {
+        let (r, c) = (row + dr, col + dc);
+        let [a, b, x, d] = [
+            inside(r, c),
+            inside(r, c + 1),
+            inside(r + 1, c),
+            inside(r + 1, c + 1),
+        ];
+        (a && d && !b && !x) || (b && x && !a && !d)
+    })
+}
+
+// Whether adding the cell could cut part of the outside off from the rest. With no
+// pinches, the outside stays connected as long as the outside cells around the new
+// one form a single unbroken run, since neighbouring ring cells share an edge.
+// Off-grid counts as outside. Two runs might still meet further away; those cells
+// are just left out.
+fn encloses(region: &Grid<bool>, row: usize, col: usize) -> bool {
+    const RING: [(isize, isize); 8] = [
+        (-1, 0),
+        (-1, 1),
+        (0, 1),
+        (1, 1),
+        (1, 0),
+        (1, -1),
+        (0, -1),
+        (-1, -1),
+    ];
+    let inside = |(dr, dc): (isize, isize)| {
+        let (r, c) = (row as isize + dr, col as isize + dc);
+        r >= 0
+            && c >= 0
+            && region
+                .get(&Point::new(r as usize, c as usize))
+                .copied()
+                .unwrap_or(false)
+    };
+    let ring = RING.map(inside);
+    let runs = (0..8).filter(|&i| !ring[i] && ring[(i + 7) % 8]).count();
+    runs > 1
+}
+
+// Add the cell to the blob and queue up its outside neighbours
+fn grow(region: &mut Grid<bool>, queued: &mut Grid<bool>, frontier: &mut Vec<Point>, cell: Point) {
+    region[cell] = true;
+    for n in region.neighbours4(cell) {
+        if !region[n] && !queued[n] {
+            queued[n] = true;
+            frontier.push(n);
+        }
+    }
+}
+
+/// A `size` by `size` field with one pipe loop through S and junk pipes everywhere
+/// else. The loop is the outline of a randomly grown blob of cells with no holes or
+/// corner-only contacts, so it never touches itself.
+pub fn day10(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(3);
+    let cells = size - 1;
+    let mut region = Grid::new(cells, cells, vec![false; cells * cells]);
+    // cells next to the blob that it could grow into
+    let mut queued = region.clone();
+    let mut frontier = Vec::new();
+    let seed = Point::new(rng.below(cells), rng.below(cells));
+    queued[seed] = true;
+    grow(&mut region, &mut queued, &mut frontier, seed);
+    let target = 1 + rng.below(cells * cells / 2);
+    let mut grown = 1;
+    let mut attempts = 0;
+    while grown < target && !frontier.is_empty() && attempts < target * 20 {
+        attempts += 1;
+        // a rejected cell stays put, growing elsewhere may make it fit later
+        let i = rng.below(frontier.len());
+        let cell = frontier[i];
+        if pinches(&region, cell.row, cell.col) || encloses(&region, cell.row, cell.col) {
+            continue;
+        }
+        frontier.swap_remove(i);
+        grow(&mut region, &mut queued, &mut frontier, cell);
+        grown += 1;
+    }
+
+    // each grid point is a tile, joined to its neighbour wherever the edge between
+    // them separates a cell inside the blob from one outside
+    let inside = |r: usize, c: usize| {
+        r >= 1
+            && c >= 1
+            && region
+                .get(&Point::new(r - 1, c - 1))
+                .copied()
+                .unwrap_or(false)
+    };
+    let mut tiles = Vec::with_capacity(size * size);
+    let mut on_loop = Vec::new();
+    let junk = ['|', '-', 'L', 'J', '7', 'F', '.', '.', '.'];
+    for r in 0..size {
+        for c in 0..size {
+            // cells around the point, offset by one so they're never negative
+            let [nw, ne, sw, se] = [
+                inside(r, c),
+                inside(r, c + 1),
+                inside(r + 1, c),
+                inside(r + 1, c + 1),
+            ];
+            let (north, south, west, east) = (nw != ne, sw != se, nw != sw, ne != se);
+            let tile = match (north, south, west, east) {
+                (true, true, false, false) => '|',
+                (false, false, true, true) => '-',
+                (true, false, false, true) => 'L',
+                (true, false, true, false) => 'J',
+                (false, true, true, false) => '7',
+                (false, true, false, true) => 'F',
+                _ => *rng.pick(&junk),
+            };
+            if north || south || west || east {
+                on_loop.push(Point::new(r, c));
+            }
+            tiles.push(tile);
+        }
+    }
+    let mut grid = Grid::new(size, size, tiles);
+    let start = *rng.pick(&on_loop);
+    grid[start] = 'S';
+    // a junk pipe pointing into S would look like a way onto the loop
+    let points_at_start = |tile: char, from: Point| match tile {
+        '|' => from.row + 1 == start.row || from.row == start.row + 1,
+        '-' => from.col + 1 == start.col || from.col == start.col + 1,
+        'L' => from.row == start.row + 1 || from.col + 1 == start.col,
+        'J' => from.row == start.row + 1 || from.col == start.col + 1,
+        '7' => from.row + 1 == start.row || from.col == start.col + 1,
+        'F' => from.row + 1 == start.row || from.col + 1 == start.col,
+        _ => false,
+    };
+    let loop_points: HashSet<Point> = on_loop.into_iter().collect();
+    for n in grid.neighbours4(start).collect::<Vec<_>>() {
+        if !loop_points.contains(&n) && points_at_start(grid[n], n) {
+            grid[n] = '.';
+        }
+    }
+    grid.to_string()
+}
+
+/// A `size` by `size` image with sparse galaxies and a few guaranteed empty rows and
+/// columns to expand
+pub fn day11(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let mut grid = Grid::new(size, size, vec!['.'; size * size]);
+    let empty_rows: HashSet<usize> = (0..size / 10 + 1).map(|_| rng.below(size)).collect();
+    let empty_cols: HashSet<usize> = (0..size / 10 + 1).map(|_| rng.below(size)).collect();
+    for r in 0..size {
+        for c in 0..size {
+            if !empty_rows.contains(&r) && !empty_cols.contains(&c) && rng.chance(0.02) {
+                grid[Point::new(r, c)] = '#';
+            }
+        }
+    }
+    grid.to_string()
+}
+
+/// `size` rows of springs, each read off a real arrangement and then partly hidden,
+/// so every row has at least one way to fit
+pub fn day12(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let len = rng.range(4..21) as usize;
+        let mut springs: Vec<bool> = (0..len).map(|_| rng.chance(0.45)).collect();
+        springs[rng.below(len)] = true;
+        let groups: Vec<String> = springs
+            .split(|x| !x)
+            .filter(|x| !x.is_empty())
+            .map(|x| x.len().to_string())
+            .collect();
+        let records: String = springs
+            .iter()
+            .map(|&damaged| match (rng.chance(0.5), damaged) {
+                (true, _) => '?',
+                (false, true) => '#',
+                (false, false) => '.',
+            })
+            .collect();
+        writeln!(out, "{records} {}", groups.join(",")).unwrap();
+    }
+    out
+}
+
+// Mirror image of `i` across a line before `line`, if it falls inside `len`
+fn mirror(i: usize, line: usize, len: usize) -> Option<usize> {
+    let j = (2 * line).checked_sub(i + 1)?;
+    (j < len).then_some(j)
+}
+
+fn day13_pattern(rng: &mut Rng) -> Grid<bool> {
+    loop {
+        let (height, width) = (rng.range(5..18) as usize, rng.range(5..18) as usize);
+        // a perfect fold between columns, off centre so some columns aren't mirrored
+        let col_line = 1 + rng.below(width - 1);
+        let row_line = 1 + rng.below(height - 1);
+        let base: Vec<bool> = (0..width * height).map(|_| rng.chance(0.5)).collect();
+        let canonical =
+            |i: usize, line: usize, len: usize| i.min(mirror(i, line, len).unwrap_or(i));
+        let mut grid = Grid::new(width, height, vec![false; width * height]);
+        for r in 0..height {
+            for c in 0..width {
+                let (cr, cc) = (
+                    canonical(r, row_line, height),
+                    canonical(c, col_line, width),
+                );
+                grid[Point::new(r, c)] = base[cr * width + cc];
+            }
+        }
+
+        // the smudge breaks the row fold in exactly one cell, leaving the column fold alone
+        let free_cols: Vec<usize> = (0..width)
+            .filter(|&c| mirror(c, col_line, width).is_none())
+            .collect();
+        let folded_rows: Vec<usize> = (0..height)
+            .filter(|&r| mirror(r, row_line, height).is_some())
+            .collect();
+        if free_cols.is_empty() {
+            continue;
+        }
+        let smudge = Point::new(*rng.pick(&folded_rows), *rng.pick(&free_cols));
+        grid[smudge] = !grid[smudge];
+        let grid = if rng.chance(0.5) {
+            grid.transpose()
+        } else {
+            grid
+        };
+
+        let reflections = find_reflections(&BitGrid::from(&grid), 1);
+        let count = |differences| {
+            reflections
+                .iter()
+                .filter(|x| x.differences == differences)
+                .count()
+        };
+        if count(0) == 1 && count(1) == 1 {
+            return grid;
+        }
+    }
+}
+
+/// `size` mirror patterns, each with exactly one perfect reflection and exactly one
+/// line that is a single smudge away from perfect
+pub fn day13(rng: &mut Rng, size: usize) -> String {
+    let patterns: Vec<String> = (0..size)
+        .map(|_| {
+            day13_pattern(rng)
+                .map(|&x| if x { '#' } else { '.' })
+                .to_string()
+        })
+        .collect();
+    patterns.join("\n")
+}
+
+/// A `size` by `size` platform of round and cube rocks
+pub fn day14(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let cells: Vec<char> = (0..size * size)
+        .map(|_| match rng.below(20) {
+            0..=3 => 'O',
+            4..=6 => '#',
+            _ => '.',
+        })
+        .collect();
+    Grid::new(size, size, cells).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{self, ProgressStyle, Status};
+    use crate::{Options, SOLUTIONS};
+
+    #[test]
+    fn every_generated_input_solves() {
+        // days fresh from new-day have no generator yet, which shouldn't fail the build
+        let mut skipped = Vec::new();
+        for solution in SOLUTIONS {
+            let Some(generator) = find_generator(solution.day) else {
+                skipped.push(format!("day {} part {}", solution.day, solution.part));
+                continue;
+            };
+            for seed in 0..3 {
+                for size in [1, 5, 20] {
+                    let input = (generator.generate)(&mut Rng::new(seed), size);
+                    let outcome = runner::run_isolated(
+                        solution,
+                        &input,
+                        &Options::default(),
+                        None,
+                        ProgressStyle::Hidden,
+                    );
+                    assert_eq!(
+                        outcome.status,
+                        Status::Ok,
+                        "day {} part {} with seed {seed} and size {size}:\n{input}",
+                        solution.day,
+                        solution.part
+                    );
+                }
+            }
+        }
+        if !skipped.is_empty() {
+            eprintln!("no generator for {}, skipped", skipped.join(", "));
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+/// One solver's timings from a benchmark run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timing {
+    pub day: u8,
+    pub part: u8,
+    /// Timings are only comparable when the input is the same
+    pub input_hash: String,
+    /// None when any of the runs didn't finish with an answer
+    pub median_ms: Option<f64>,
+    pub min_ms: Option<f64>,
+    pub status: String,
+}
+
+/// A benchmark run, stored as one JSON line in the history file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    pub timestamp: u64,
+    pub commit: String,
+    /// Whether the working tree had uncommitted changes
+    pub dirty: bool,
+    pub runs: usize,
+    pub timings: Vec<Timing>,
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Io(PathBuf, std::io::Error),
+    Corrupt { line: usize, error: String },
+    Empty,
+    NoBaseline(String),
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            HistoryError::Corrupt { line, error } => {
+                write!(f, "run history line {line} is malformed: {error}")
+            }
+            HistoryError::Empty => write!(f, "no runs recorded yet, run bench first"),
+            HistoryError::NoBaseline(commit) => {
+                write!(f, "no earlier run recorded at commit {commit}")
+            }
+        }
+    }
+}
+
+impl Error for HistoryError {}
+
+// FNV-1a, because std's hashers aren't guaranteed stable between releases
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, x| {
+        (hash ^ x as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = process::Command::new("git").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The checked out commit and whether there are local changes on top of it
+pub fn current_commit() -> (String, bool) {
+    let commit = git(&["rev-parse", "HEAD"]).unwrap_or("unknown".to_string());
+    let dirty = git(&["status", "--porcelain"]).is_some_and(|x| !x.is_empty());
+    (commit, dirty)
+}
+
+pub fn median(times: &mut [Duration]) -> Option<Duration> {
+    times.sort();
+    let mid = times.len() / 2;
+    match times.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(times[mid]),
+        _ => Some((times[mid - 1] + times[mid]) / 2),
+    }
+}
+
+pub fn load(path: &Path) -> Result<Vec<Run>, HistoryError> {
+    let text = match fs::read_to_string(path) {
+        Ok(x) => x,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(HistoryError::Io(path.to_owned(), e)),
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, x)| !x.is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| HistoryError::Corrupt {
+                line: i + 1,
+                error: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn append(path: &Path, run: &Run) -> Result<(), HistoryError> {
+    let io_error = |e| HistoryError::Io(path.to_owned(), e);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(io_error)?;
+    let line = serde_json::to_string(run).expect("runs always serialise");
+    writeln!(file, "{line}").map_err(io_error)
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    pub day: u8,
+    pub part: u8,
+    /// Where the earlier timing came from
+    pub before_commit: String,
+    pub before_ms: f64,
+    pub after_ms: f64,
+}
+
+impl Comparison {
+    pub fn change_percent(&self) -> f64 {
+        (self.after_ms - self.before_ms) / self.before_ms * 100.0
+    }
+
+    pub fn regressed(&self, threshold_percent: f64) -> bool {
+        self.change_percent() > threshold_percent
+    }
+}
+
+/// Match each timing in the latest run against the same solver and input, either
+/// in the most recent earlier run that has one or in the latest run at a baseline
+/// commit. Solvers with nothing to compare against are left out.
+pub fn compare(runs: &[Run], baseline: Option<&str>) -> Result<Vec<Comparison>, HistoryError> {
+    let (latest, earlier) = runs.split_last().ok_or(HistoryError::Empty)?;
+    let earlier: Vec<&Run> = match baseline {
+        Some(commit) => {
+            let run = earlier
+                .iter()
+                .rev()
+                .find(|x| x.commit.starts_with(commit))
+                .ok_or(HistoryError::NoBaseline(commit.to_string()))?;
+            vec![run]
+        }
+        None => earlier.iter().rev().collect(),
+    };
+
+    let comparisons = latest
+        .timings
+        .iter()
+        .filter_map(|after| {
+            let after_ms = after.median_ms?;
+            earlier.iter().find_map(|run| {
+                let before = run.timings.iter().find(|x| {
+                    (x.day, x.part, &x.input_hash) == (after.day, after.part, &after.input_hash)
+                })?;
+                Some(Comparison {
+                    day: after.day,
+                    part: after.part,
+                    before_commit: run.commit.clone(),
+                    before_ms: before.median_ms?,
+                    after_ms,
+                })
+            })
+        })
+        .collect();
+    Ok(comparisons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(day: u8, input_hash: &str, median_ms: f64) -> Timing {
+        Timing {
+            day,
+            part: 1,
+            input_hash: input_hash.to_string(),
+            median_ms: Some(median_ms),
+            min_ms: Some(median_ms),
+            status: "ok".to_string(),
+        }
+    }
+
+    fn run(commit: &str, timings: Vec<Timing>) -> Run {
+        Run {
+            timestamp: 0,
+            commit: commit.to_string(),
+            dirty: false,
+            runs: 5,
+            timings,
+        }
+    }
+
+    #[test]
+    fn median_of_odd_and_even_runs() {
+        let ms = Duration::from_millis;
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [ms(30), ms(10), ms(20)]), Some(ms(20)));
+        assert_eq!(median(&mut [ms(40), ms(10), ms(30), ms(20)]), Some(ms(25)));
+    }
+
+    #[test]
+    fn slowdowns_past_the_threshold_are_flagged() {
+        let runs = [
+            run("aaa", vec![timing(1, "x", 100.0), timing(2, "x", 100.0)]),
+            run("bbb", vec![timing(1, "x", 125.0), timing(2, "x", 105.0)]),
+        ];
+        let comparisons = compare(&runs, None).unwrap();
+        assert_eq!(comparisons.len(), 2);
+        assert!(comparisons[0].regressed(10.0));
+        assert!(!comparisons[1].regressed(10.0));
+        assert_eq!(comparisons[1].change_percent(), 5.0);
+        // speeding up is never a regression
+        let faster = [runs[1].clone(), runs[0].clone()];
+        assert!(compare(&faster, None)
+            .unwrap()
+            .iter()
+            .all(|x| !x.regressed(0.0)));
+    }
+
+    #[test]
+    fn missing_baselines_are_handled() {
+        let runs = [
+            run("aaa", vec![timing(1, "x", 100.0)]),
+            run("bbb", vec![timing(1, "x", 100.0), timing(2, "x", 50.0)]),
+        ];
+        // day 2 has nothing earlier to compare against, so it's left out
+        let comparisons = compare(&runs, None).unwrap();
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(
+            (comparisons[0].day, comparisons[0].before_commit.as_str()),
+            (1, "aaa")
+        );
+        assert!(matches!(
+            compare(&runs, Some("ccc")),
+            Err(HistoryError::NoBaseline(x)) if x == "ccc"
+        ));
+        assert!(matches!(compare(&[], None), Err(HistoryError::Empty)));
+        assert!(compare(&runs[..1], None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn different_inputs_are_not_compared() {
+        let runs = [
+            run("aaa", vec![timing(1, "x", 100.0)]),
+            run("bbb", vec![timing(1, "y", 10.0)]),
+            run("ccc", vec![timing(1, "x", 200.0)]),
+        ];
+        // skips the faster run on another input and finds the one on the same input
+        let comparisons = compare(&runs, None).unwrap();
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].before_commit, "aaa");
+        assert_eq!(comparisons[0].before_ms, 100.0);
+        // and a baseline whose only timing is on another input gives nothing
+        assert!(compare(&runs, Some("bbb")).unwrap().is_empty());
+    }
+}
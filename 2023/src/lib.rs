@@ -0,0 +1,280 @@
+use aoc_core::{cancel::CancelToken, progress::Progress, SolveError, SolveResult};
+use std::time::{Duration, Instant};
+
+pub use aoc_core::{bitgrid, cancel, cycle, grid, parse, point, progress, rng};
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod differential;
+pub mod generate;
+pub mod report;
+pub mod runner;
+
+/// Knobs for the days that can be run in more than one way, plus the runner's
+/// handles for stopping a solver early and following how far along it is
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub unfold_factor: usize,
+    pub unfold_separator: day12::RecordType,
+    pub tilt_program: day14::TiltProgram,
+    pub spin_cycles: usize,
+    pub load_edge: point::Direction,
+    pub frame_delay: Duration,
+    pub cancel: CancelToken,
+    pub progress: Progress,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            unfold_factor: 5,
+            unfold_separator: day12::RecordType::Unknown,
+            tilt_program: "NWSE".parse().unwrap(),
+            spin_cycles: 1_000_000_000,
+            load_edge: point::Direction::North,
+            frame_delay: Duration::from_millis(100),
+            cancel: CancelToken::new(),
+            progress: Progress::new(),
+        }
+    }
+}
+
+/// A solver's answer, along with how long parsing and solving each took
+pub struct Solved {
+    pub result: SolveResult,
+    /// None when the day parses as it goes rather than up front
+    pub parse_time: Option<Duration>,
+    pub solve_time: Duration,
+}
+
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    pub solve: fn(&str, &Options) -> Solved,
+}
+
+// Time the day's parser and its solver separately, the solver getting whatever the
+// parser made so nothing is parsed twice
+fn timed<'a, T, E: Into<SolveError>>(
+    input: &'a str,
+    parse: impl FnOnce(&'a str) -> Result<T, E>,
+    solve: impl FnOnce(T) -> SolveResult,
+) -> Solved {
+    let start = Instant::now();
+    let parsed = parse(input);
+    let parse_time = Some(start.elapsed());
+    let parsed = match parsed {
+        Ok(x) => x,
+        Err(e) => {
+            return Solved {
+                result: Err(e.into()),
+                parse_time,
+                solve_time: Duration::ZERO,
+            }
+        }
+    };
+    let start = Instant::now();
+    let result = solve(parsed);
+    Solved {
+        result,
+        parse_time,
+        solve_time: start.elapsed(),
+    }
+}
+
+// For days without a separate parser
+fn timed_whole(solve: impl FnOnce() -> SolveResult) -> Solved {
+    let start = Instant::now();
+    let result = solve();
+    Solved {
+        result,
+        parse_time: None,
+        solve_time: start.elapsed(),
+    }
+}
+
+pub const SOLUTIONS: &[Solution] = &[
+    Solution {
+        day: 1,
+        part: 1,
+        solve: |input, _| timed_whole(|| day01::day_1_p1(input)),
+    },
+    Solution {
+        day: 1,
+        part: 2,
+        solve: |input, _| timed_whole(|| day01::day_1_p2(input)),
+    },
+    Solution {
+        day: 2,
+        part: 1,
+        solve: |input, _| timed(input, day02::get_day2_input, day02::day_2_p1),
+    },
+    Solution {
+        day: 2,
+        part: 2,
+        solve: |input, _| timed(input, day02::get_day2_input, day02::day_2_p2),
+    },
+    Solution {
+        day: 3,
+        part: 1,
+        solve: |input, _| timed(input, day03::get_day3_input, day03::day3_p1),
+    },
+    Solution {
+        day: 3,
+        part: 2,
+        solve: |input, _| timed(input, day03::get_day3_input, day03::day3_p2),
+    },
+    Solution {
+        day: 4,
+        part: 1,
+        solve: |input, _| timed(input, day04::get_day4_input, day04::day4_p1),
+    },
+    Solution {
+        day: 4,
+        part: 2,
+        solve: |input, _| timed(input, day04::get_day4_input, day04::day4_p2),
+    },
+    Solution {
+        day: 5,
+        part: 1,
+        solve: |input, _| timed(input, day05::read_day5_input, day05::day5_p1),
+    },
+    Solution {
+        day: 5,
+        part: 2,
+        solve: |input, _| timed(input, day05::read_day5_input, day05::day5_p2),
+    },
+    Solution {
+        day: 6,
+        part: 1,
+        solve: |input, _| timed(input, day06::get_day6_input_p1, day06::day6_p1),
+    },
+    Solution {
+        day: 6,
+        part: 2,
+        solve: |input, _| timed(input, day06::get_day6_input_p2, day06::day6_p2),
+    },
+    Solution {
+        day: 7,
+        part: 1,
+        solve: |input, _| {
+            timed(
+                input,
+                |x| day07::get_day7_input(x, day07::Part::One),
+                day07::day7_p1,
+            )
+        },
+    },
+    Solution {
+        day: 7,
+        part: 2,
+        solve: |input, _| {
+            timed(
+                input,
+                |x| day07::get_day7_input(x, day07::Part::Two),
+                day07::day7_p2,
+            )
+        },
+    },
+    Solution {
+        day: 8,
+        part: 1,
+        solve: |input, _| timed(input, day08::get_day8_input, day08::day8_p1),
+    },
+    Solution {
+        day: 8,
+        part: 2,
+        solve: |input, _| timed(input, day08::get_day8_input, day08::day8_p2),
+    },
+    Solution {
+        day: 9,
+        part: 1,
+        solve: |input, _| timed(input, day09::get_day9_input, day09::day9_p1),
+    },
+    Solution {
+        day: 9,
+        part: 2,
+        solve: |input, _| timed(input, day09::get_day9_input, day09::day9_p2),
+    },
+    Solution {
+        day: 10,
+        part: 1,
+        solve: |input, _| timed(input, day10::get_day10_input, day10::day10_p1),
+    },
+    Solution {
+        day: 10,
+        part: 2,
+        solve: |input, _| timed(input, day10::get_day10_input, day10::day10_p2),
+    },
+    Solution {
+        day: 11,
+        part: 1,
+        solve: |input, _| timed(input, day11::get_day11_input, day11::day11_p1),
+    },
+    Solution {
+        day: 11,
+        part: 2,
+        solve: |input, _| timed(input, day11::get_day11_input, day11::day11_p2),
+    },
+    Solution {
+        day: 12,
+        part: 1,
+        solve: |input, _| timed(input, day12::get_day12_input, day12::day12_p1),
+    },
+    Solution {
+        day: 12,
+        part: 2,
+        solve: |input, opts| {
+            timed(input, day12::get_day12_input, |x| {
+                day12::day12_p2(x, opts.unfold_factor, &opts.unfold_separator)
+            })
+        },
+    },
+    Solution {
+        day: 13,
+        part: 1,
+        solve: |input, _| timed(input, day13::get_day13_input, day13::day13_p1),
+    },
+    Solution {
+        day: 13,
+        part: 2,
+        solve: |input, _| timed(input, day13::get_day13_input, day13::day13_p2),
+    },
+    Solution {
+        day: 14,
+        part: 1,
+        solve: |input, _| timed(input, day14::get_day14_input, day14::day14_p1),
+    },
+    Solution {
+        day: 14,
+        part: 2,
+        solve: |input, opts| {
+            timed(input, day14::get_day14_input, |x| {
+                day14::day14_p2(
+                    x,
+                    &opts.tilt_program,
+                    opts.spin_cycles,
+                    &opts.load_edge,
+                    &opts.cancel,
+                    &opts.progress,
+                )
+            })
+        },
+    },
+];
+
+pub fn find_solution(day: u8, part: u8) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|x| x.day == day && x.part == part)
+}
@@ -0,0 +1,38 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Writes every enabled record to stderr, keeping stdout for answers
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "{:>5} {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Info by default, each -v or -q moves one level up or down
+pub fn init(verbose: u8, quiet: u8) {
+    let levels = [
+        LevelFilter::Off,
+        LevelFilter::Error,
+        LevelFilter::Warn,
+        LevelFilter::Info,
+        LevelFilter::Debug,
+        LevelFilter::Trace,
+    ];
+    let index = (3 + verbose as isize - quiet as isize).clamp(0, 5);
+    log::set_logger(&StderrLogger).expect("logger already set");
+    log::set_max_level(levels[index as usize]);
+}
@@ -1,18 +1,713 @@
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
-pub mod day08;
-pub mod day09;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-
-fn main() {
-    day14::day14_p2();
+use aoc_2023::{
+    day12, day13, day14, differential, find_solution, generate, point, report, runner, Options,
+    SOLUTIONS,
+};
+use clap::{error::ErrorKind, ArgAction, Args, CommandFactory, Parser, Subcommand};
+use log::{debug, info};
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, fs, panic, path::Path, path::PathBuf, process::ExitCode};
+
+mod client;
+mod config;
+mod history;
+mod logger;
+mod scaffold;
+mod submit;
+#[cfg(test)]
+mod test_server;
+
+/// Per-solver budget for --all, so one slow day can't hold up the rest
+const DEFAULT_ALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    run: RunArgs,
+    /// Show more diagnostics, repeat for more detail
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Show fewer diagnostics, repeat for fewer still
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    quiet: u8,
+    /// Settings file to use instead of the nearest aoc.toml
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate and register the module and input placeholders for a new day
+    NewDay { day: u8 },
+    /// Download a day's puzzle input into the input directory, unless it's already there
+    Fetch(ServerArgs),
+    /// Solve a day and post the answer, unless the answer log rules it out
+    Submit {
+        #[command(flatten)]
+        server: ServerArgs,
+        #[arg(long, default_value_t = 1)]
+        part: u8,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Write a random but valid puzzle input for a day
+    Generate {
+        #[arg(long)]
+        day: u8,
+        /// The same seed and size always give the same input
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Roughly how many lines or how wide a grid, defaults to about the real puzzle's
+        #[arg(long)]
+        size: Option<usize>,
+        /// Write the input here instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<PathBuf>,
+    },
+    /// Check optimised implementations against naive ones on generated inputs
+    Differential {
+        /// Seed for the first pair, each pair after that uses the next one
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Generated inputs to try per pair
+        #[arg(long, default_value_t = 1000)]
+        cases: usize,
+    },
+    /// Time solvers over several runs and add their medians to the run history
+    Bench(BenchArgs),
+    /// Flag solvers whose median time regressed in the latest benchmark run
+    Compare {
+        /// Compare against the latest run at this commit instead of the previous one
+        #[arg(long, value_name = "COMMIT")]
+        baseline: Option<String>,
+        /// Percentage slowdown that counts as a regression
+        #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+        threshold: f64,
+    },
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Only time this day, defaults to every registered day
+    #[arg(long)]
+    day: Option<u8>,
+    /// Only time this part
+    #[arg(long)]
+    part: Option<u8>,
+    /// Times to run each solver
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+    /// Give up on a solver after this many seconds per run, defaults to the
+    /// configured timeouts, or 60
+    #[arg(long, value_name = "SECS", value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Puzzle input file, defaults to the configured input pattern
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Use the Nth example for the day instead of the puzzle input
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "1", conflicts_with = "input")]
+    example: Option<u8>,
+}
+
+impl InputArgs {
+    fn path(&self, config: &config::Config, day: u8, part: u8) -> PathBuf {
+        match (&self.input, self.example) {
+            (Some(path), _) => path.clone(),
+            (None, Some(n)) => config.example_path(day, part, n),
+            (None, None) => config.input_path(day, part),
+        }
+    }
+}
+
+#[derive(Args)]
+struct ServerArgs {
+    #[arg(long)]
+    day: u8,
+    /// Puzzle year, defaults to the configured year
+    #[arg(long)]
+    year: Option<u16>,
+    /// Server to talk to, defaults to $AOC_BASE_URL, then the configured base_url,
+    /// then adventofcode.com
+    #[arg(long)]
+    base_url: Option<String>,
+}
+
+impl ServerArgs {
+    fn client(&self, config: &config::Config) -> Result<client::Client, client::ClientError> {
+        let base_url = self
+            .base_url
+            .clone()
+            .or_else(|| env::var("AOC_BASE_URL").ok())
+            .or_else(|| config.base_url.clone())
+            .unwrap_or(client::DEFAULT_BASE_URL.to_string());
+        Ok(client::Client::new(
+            &base_url,
+            client::find_session(config.session_file.as_deref())?,
+            self.year.unwrap_or(config.year),
+            config.state_dir(),
+        ))
+    }
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[arg(long)]
+    day: Option<u8>,
+    /// Run every registered day and part and summarise the results
+    #[arg(long, conflicts_with_all = ["day", "input", "example", "animate"])]
+    all: bool,
+    #[arg(long, default_value_t = 1)]
+    part: u8,
+    #[command(flatten)]
+    input: InputArgs,
+    /// How to print results, defaults to the configured output format
+    #[arg(long)]
+    format: Option<config::OutputFormat>,
+    /// Write results to this file instead of stdout
+    #[arg(long, value_name = "FILE")]
+    output_file: Option<PathBuf>,
+    /// Number of copies of each row when unfolding day 12
+    #[arg(long, default_value_t = 5)]
+    unfold_factor: usize,
+    /// Spring record ('#', '.' or '?') joining the copies when unfolding day 12
+    #[arg(long, default_value = "?")]
+    unfold_separator: day12::RecordType,
+    /// Show each day 13 pattern with its smudge fixed and highlighted, on stderr so
+    /// it stays out of the results
+    #[arg(long)]
+    show_smudges: bool,
+    /// Tilts making up one day 14 spin cycle, as direction letters
+    #[arg(long, default_value = "NWSE")]
+    tilt_program: day14::TiltProgram,
+    /// Number of spin cycles to run for day 14
+    #[arg(long, default_value_t = 1_000_000_000)]
+    spin_cycles: usize,
+    /// Edge ('N', 'E', 'S' or 'W') to measure the day 14 load against
+    #[arg(long, default_value = "N")]
+    load_edge: point::Direction,
+    /// Animate each day 14 tilt in the terminal instead of solving
+    #[arg(long)]
+    animate: bool,
+    /// Delay between day 14 animation frames in milliseconds
+    #[arg(long, default_value_t = 100)]
+    frame_delay_ms: u64,
+    /// Give up on a solver after this many seconds, defaults to the configured
+    /// timeouts, or 60 for --all
+    #[arg(long, value_name = "SECS", value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("{e}"))
+}
+
+impl RunArgs {
+    fn options(&self) -> Options {
+        Options {
+            unfold_factor: self.unfold_factor,
+            unfold_separator: self.unfold_separator.clone(),
+            tilt_program: self.tilt_program.clone(),
+            spin_cycles: self.spin_cycles,
+            load_edge: self.load_edge,
+            frame_delay: Duration::from_millis(self.frame_delay_ms),
+            ..Options::default()
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    logger::init(cli.verbose, cli.quiet);
+    let config = match cli
+        .config
+        .as_deref()
+        .map_or_else(config::Config::find, config::Config::load)
+    {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Invalid configuration: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match cli.command {
+        Some(Command::NewDay { day }) => new_day(day, &config),
+        Some(Command::Fetch(args)) => fetch(&args, &config),
+        Some(Command::Submit {
+            server,
+            part,
+            input,
+        }) => submit(&server, part, &input, &config),
+        Some(Command::Generate {
+            day,
+            seed,
+            size,
+            output_file,
+        }) => generate(day, seed, size, output_file.as_deref()),
+        Some(Command::Differential { seed, cases }) => differential(seed, cases),
+        Some(Command::Bench(args)) => bench(&args, &config),
+        Some(Command::Compare {
+            baseline,
+            threshold,
+        }) => compare(baseline.as_deref(), threshold, &config),
+        None => run(&cli.run, &config),
+    }
+}
+
+fn new_day(day: u8, config: &config::Config) -> ExitCode {
+    match scaffold::new_day(Path::new(env!("CARGO_MANIFEST_DIR")), config, day) {
+        Ok(created) => {
+            for path in created {
+                println!("Created {}", path.display());
+            }
+            println!("Registered day {day} in lib.rs");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Couldn't add day {day}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn fetch(args: &ServerArgs, config: &config::Config) -> ExitCode {
+    let path = config.input_path(args.day, 1);
+    // no need for a session when there's nothing to download
+    if client::is_cached(&path) {
+        println!(
+            "Day {} input is already cached in {}",
+            args.day,
+            path.display()
+        );
+        return ExitCode::SUCCESS;
+    }
+    match args
+        .client(config)
+        .and_then(|x| x.fetch_input(args.day, &path))
+    {
+        Ok(()) => {
+            println!("Saved day {} input to {}", args.day, path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Couldn't fetch day {}: {e}", args.day);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn generate(day: u8, seed: u64, size: Option<usize>, output_file: Option<&Path>) -> ExitCode {
+    let Some(generator) = generate::find_generator(day) else {
+        Cli::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("no generator for day {day}"),
+            )
+            .exit()
+    };
+    let size = size.unwrap_or(generator.default_size);
+    let input = (generator.generate)(&mut aoc_2023::rng::Rng::new(seed), size);
+    let written = match output_file {
+        Some(path) => {
+            fs::write(path, input).map_err(|e| format!("Couldn't write {}: {e}", path.display()))
+        }
+        None => io::stdout()
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("Couldn't write the input: {e}")),
+    };
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn differential(seed: u64, cases: usize) -> ExitCode {
+    let mut agreed = true;
+    for (pair, seed) in differential::PAIRS.iter().zip(seed..) {
+        info!("Checking {}", pair.name);
+        match (pair.check)(seed, cases) {
+            Ok(()) => println!("{}: agreed on {cases} inputs", pair.name),
+            Err(e) => {
+                println!("{}: diverged with seed {seed} on {e}", pair.name);
+                agreed = false;
+            }
+        }
+    }
+    if agreed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn read_input(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {e}", path.display()))
+}
+
+fn submit(server: &ServerArgs, part: u8, input: &InputArgs, config: &config::Config) -> ExitCode {
+    let day = server.day;
+    let Some(solution) = find_solution(day, part) else {
+        Cli::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("no solution for day {day} part {part}"),
+            )
+            .exit()
+    };
+    let input = match read_input(&input.path(config, day, part)) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let answer = match (solution.solve)(&input, &Options::default()).result {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Day {day} part {part} failed: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let result = server
+        .client(config)
+        .map_err(submit::SubmitError::from)
+        .and_then(|client| {
+            let mut log = submit::AnswerLog::open(&client.state_dir().join("answers.tsv"))?;
+            submit::submit(&client, &mut log, day, part, &answer)
+        });
+    match result {
+        Ok((verdict, text)) => {
+            println!("Day {day} part {part}: {answer} is {verdict}");
+            println!("{text}");
+            if verdict == submit::Verdict::Correct {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("Not submitting {answer} for day {day} part {part}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Progress is informational, so it goes quiet along with info logging
+fn progress_style() -> runner::ProgressStyle {
+    if log::max_level() < log::LevelFilter::Info {
+        runner::ProgressStyle::Hidden
+    } else if io::stderr().is_terminal() {
+        runner::ProgressStyle::Line
+    } else {
+        runner::ProgressStyle::Log
+    }
+}
+
+// JSON and CSV always get a record, text only shows what a person needs to see
+fn write_results(
+    args: &RunArgs,
+    config: &config::Config,
+    text: &str,
+    records: &[report::Record],
+) -> Result<(), String> {
+    let mut out: Box<dyn Write> = match &args.output_file {
+        Some(path) => Box::new(
+            fs::File::create(path)
+                .map_err(|e| format!("Couldn't create {}: {e}", path.display()))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    let written = match args.format.unwrap_or(config.output) {
+        config::OutputFormat::Text => out.write_all(text.as_bytes()),
+        config::OutputFormat::Json => report::write_json(records, &mut out),
+        config::OutputFormat::Csv => report::write_csv(records, &mut out),
+    };
+    written
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("Couldn't write results: {e}"))
+}
+
+fn run_all(args: &RunArgs, config: &config::Config) -> ExitCode {
+    // panics are reported in the summary, only show the default output when asked
+    panic::set_hook(Box::new(|info| debug!("{info}")));
+    let options = args.options();
+    let runs: Vec<_> = SOLUTIONS
+        .iter()
+        .map(|solution| {
+            info!("Running day {} part {}", solution.day, solution.part);
+            let timeout = args
+                .timeout
+                .or(config.timeout(solution.day, solution.part))
+                .unwrap_or(DEFAULT_ALL_TIMEOUT);
+            let path = config.input_path(solution.day, solution.part);
+            let outcome = match read_input(&path) {
+                Ok(input) => runner::run_isolated(
+                    solution,
+                    &input,
+                    &options,
+                    Some(timeout),
+                    progress_style(),
+                ),
+                Err(e) => runner::Outcome {
+                    day: solution.day,
+                    part: solution.part,
+                    answer: None,
+                    status: runner::Status::NoInput(e),
+                    parse_time: None,
+                    solve_time: Duration::ZERO,
+                },
+            };
+            (path, outcome)
+        })
+        .collect();
+    let outcomes: Vec<_> = runs.iter().map(|(_, x)| x.clone()).collect();
+    let records: Vec<_> = runs
+        .iter()
+        .map(|(path, x)| report::Record::new(config.year, path, x))
+        .collect();
+    if let Err(e) = write_results(args, config, &runner::summary_table(&outcomes), &records) {
+        eprintln!("{e}");
+        return ExitCode::FAILURE;
+    }
+    if outcomes.iter().all(|x| x.status == runner::Status::Ok) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn bench(args: &BenchArgs, config: &config::Config) -> ExitCode {
+    panic::set_hook(Box::new(|info| debug!("{info}")));
+    let solutions: Vec<_> = SOLUTIONS
+        .iter()
+        .filter(|x| args.day.is_none_or(|day| x.day == day))
+        .filter(|x| args.part.is_none_or(|part| x.part == part))
+        .collect();
+    if solutions.is_empty() {
+        Cli::command()
+            .error(
+                ErrorKind::InvalidValue,
+                "no solutions match --day and --part",
+            )
+            .exit()
+    }
+
+    let options = Options::default();
+    let timings: Vec<_> = solutions
+        .into_iter()
+        .map(|solution| {
+            info!("Timing day {} part {}", solution.day, solution.part);
+            let path = config.input_path(solution.day, solution.part);
+            let input = match read_input(&path) {
+                Ok(x) => x,
+                Err(e) => {
+                    return history::Timing {
+                        day: solution.day,
+                        part: solution.part,
+                        input_hash: String::new(),
+                        median_ms: None,
+                        min_ms: None,
+                        status: runner::Status::NoInput(e).to_string(),
+                    }
+                }
+            };
+            let timeout = args
+                .timeout
+                .or(config.timeout(solution.day, solution.part))
+                .unwrap_or(DEFAULT_ALL_TIMEOUT);
+            let mut times = Vec::new();
+            let mut status = runner::Status::Ok;
+            for _ in 0..args.runs {
+                let outcome = runner::run_isolated(
+                    solution,
+                    &input,
+                    &options,
+                    Some(timeout),
+                    progress_style(),
+                );
+                if outcome.status != runner::Status::Ok {
+                    // no point waiting out a broken solver again
+                    status = outcome.status;
+                    break;
+                }
+                times.push(outcome.total_time());
+            }
+            let ok = status == runner::Status::Ok;
+            let millis = |x: Duration| x.as_secs_f64() * 1000.0;
+            history::Timing {
+                day: solution.day,
+                part: solution.part,
+                input_hash: history::input_hash(&input),
+                median_ms: history::median(&mut times).filter(|_| ok).map(millis),
+                min_ms: times.iter().min().filter(|_| ok).copied().map(millis),
+                status: status.to_string(),
+            }
+        })
+        .collect();
+
+    let rows: Vec<Vec<String>> = timings
+        .iter()
+        .map(|x| {
+            let millis = |x: Option<f64>| x.map_or("-".to_string(), |x| format!("{x:.3}ms"));
+            vec![
+                x.day.to_string(),
+                x.part.to_string(),
+                millis(x.median_ms),
+                millis(x.min_ms),
+                x.status.clone(),
+            ]
+        })
+        .collect();
+    print!(
+        "{}",
+        runner::table(&["day", "part", "median", "min", "status"], &rows)
+    );
+
+    let (commit, dirty) = history::current_commit();
+    let run = history::Run {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs()),
+        commit,
+        dirty,
+        runs: args.runs as usize,
+        timings,
+    };
+    let path = config.state_dir().join("history.jsonl");
+    if let Err(e) = history::append(&path, &run) {
+        eprintln!("Couldn't record the run: {e}");
+        return ExitCode::FAILURE;
+    }
+    info!("Recorded run at {} in {}", run.commit, path.display());
+    if run.timings.iter().all(|x| x.median_ms.is_some()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn compare(baseline: Option<&str>, threshold: f64, config: &config::Config) -> ExitCode {
+    let path = config.state_dir().join("history.jsonl");
+    let comparisons = match history::load(&path).and_then(|x| history::compare(&x, baseline)) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Couldn't compare runs: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if comparisons.is_empty() {
+        println!("Nothing to compare, no earlier timings for the same solvers and inputs");
+        return ExitCode::SUCCESS;
+    }
+    let rows: Vec<Vec<String>> = comparisons
+        .iter()
+        .map(|x| {
+            vec![
+                x.day.to_string(),
+                x.part.to_string(),
+                x.before_commit.chars().take(10).collect(),
+                format!("{:.3}ms", x.before_ms),
+                format!("{:.3}ms", x.after_ms),
+                format!("{:+.1}%", x.change_percent()),
+                if x.regressed(threshold) {
+                    "REGRESSED".to_string()
+                } else {
+                    String::new()
+                },
+            ]
+        })
+        .collect();
+    print!(
+        "{}",
+        runner::table(
+            &["day", "part", "against", "before", "after", "change", ""],
+            &rows
+        )
+    );
+    if comparisons.iter().any(|x| x.regressed(threshold)) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run(args: &RunArgs, config: &config::Config) -> ExitCode {
+    if args.all {
+        return run_all(args, config);
+    }
+    let Some(day) = args.day else {
+        Cli::command()
+            .error(ErrorKind::MissingRequiredArgument, "--day is required")
+            .exit()
+    };
+    let Some(solution) = find_solution(day, args.part) else {
+        Cli::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("no solution for day {day} part {}", args.part),
+            )
+            .exit()
+    };
+    let path = args.input.path(config, day, args.part);
+    let input = match read_input(&path) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let options = args.options();
+
+    if args.animate && day == 14 {
+        return match day14::day14_replay(
+            &input,
+            &options.tilt_program,
+            options.spin_cycles,
+            &options.load_edge,
+            options.frame_delay,
+        ) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Couldn't parse {}: {e}", path.display());
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if args.show_smudges && day == 13 {
+        // a bad input is reported by the solver itself below
+        // only colour the smudges when they'll be seen, not in a redirected log
+        let colour = io::stderr().is_terminal();
+        for smudge in day13::find_smudges(&input).unwrap_or_default() {
+            eprintln!("{}", smudge.render(colour));
+        }
+    }
+
+    let timeout = args.timeout.or(config.timeout(day, args.part));
+    let outcome = runner::run_isolated(solution, &input, &options, timeout, progress_style());
+    let text = match &outcome.answer {
+        Some(answer) => format!("Day {day} part {}: {answer}\n", args.part),
+        None => {
+            eprintln!("Day {day} part {} {}", args.part, outcome.status);
+            String::new()
+        }
+    };
+    let record = report::Record::new(config.year, &path, &outcome);
+    if let Err(e) = write_results(args, config, &text, &[record]) {
+        eprintln!("{e}");
+        return ExitCode::FAILURE;
+    }
+    if outcome.status == runner::Status::Ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
 }
@@ -0,0 +1,95 @@
+use crate::runner::{Outcome, Status};
+use serde::Serialize;
+use std::{io, path::Path, time::Duration};
+
+/// Bumped whenever a field is renamed, removed or changes meaning
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// One solver run, as written to JSON and CSV results files
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub input_file: String,
+    pub answer: Option<String>,
+    pub parse_time_ms: Option<f64>,
+    pub solve_time_ms: f64,
+    pub status: &'static str,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Results<'a> {
+    schema_version: u32,
+    results: &'a [Record],
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl Record {
+    pub fn new(year: u16, input_file: &Path, outcome: &Outcome) -> Record {
+        let (status, error) = match &outcome.status {
+            Status::Ok => ("ok", None),
+            Status::Failed(e) => ("failed", Some(e.clone())),
+            Status::Panicked(e) => ("panicked", Some(e.clone())),
+            Status::TimedOut(limit) => ("timed_out", Some(format!("limit {limit:?}"))),
+            Status::NoInput(e) => ("no_input", Some(e.clone())),
+        };
+        Record {
+            year,
+            day: outcome.day,
+            part: outcome.part,
+            input_file: input_file.display().to_string(),
+            answer: outcome.answer.clone(),
+            parse_time_ms: outcome.parse_time.map(millis),
+            solve_time_ms: millis(outcome.solve_time),
+            status,
+            error,
+        }
+    }
+}
+
+pub fn write_json(records: &[Record], mut out: impl io::Write) -> io::Result<()> {
+    let results = Results {
+        schema_version: SCHEMA_VERSION,
+        results: records,
+    };
+    serde_json::to_writer_pretty(&mut out, &results)?;
+    writeln!(out)
+}
+
+/// CSV has nowhere else to put it, so every row carries the schema version
+pub fn write_csv(records: &[Record], out: impl io::Write) -> io::Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record([
+        "schema_version",
+        "year",
+        "day",
+        "part",
+        "input_file",
+        "answer",
+        "parse_time_ms",
+        "solve_time_ms",
+        "status",
+        "error",
+    ])?;
+    let optional = |x: Option<String>| x.unwrap_or_default();
+    for x in records {
+        writer.write_record([
+            SCHEMA_VERSION.to_string(),
+            x.year.to_string(),
+            x.day.to_string(),
+            x.part.to_string(),
+            x.input_file.clone(),
+            optional(x.answer.clone()),
+            optional(x.parse_time_ms.map(|t| t.to_string())),
+            x.solve_time_ms.to_string(),
+            x.status.to_string(),
+            optional(x.error.clone()),
+        ])?;
+    }
+    writer.flush()
+}
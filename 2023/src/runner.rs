@@ -0,0 +1,241 @@
+use crate::{
+    cancel::CancelToken,
+    progress::{Progress, Snapshot},
+    Options, Solution, Solved,
+};
+use log::info;
+use std::{
+    any::Any,
+    fmt,
+    io::{self, Write},
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Solvers recurse deeply in places, give them as much stack as the main thread
+const SOLVER_STACK_SIZE: usize = 8 << 20;
+
+/// How to show a running solver's progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStyle {
+    Hidden,
+    /// Redraw a single line on stderr, for terminals
+    Line,
+    /// Log a line every so often, for pipes and files
+    Log,
+}
+
+impl ProgressStyle {
+    fn interval(&self) -> Option<Duration> {
+        match self {
+            ProgressStyle::Hidden => None,
+            ProgressStyle::Line => Some(Duration::from_millis(100)),
+            ProgressStyle::Log => Some(Duration::from_secs(5)),
+        }
+    }
+}
+
+fn describe(snapshot: &Snapshot) -> String {
+    match (snapshot.total, snapshot.percent()) {
+        (Some(total), Some(percent)) => format!(
+            "{} {}/{total} ({percent:.1}%)",
+            snapshot.phase, snapshot.done
+        ),
+        _ => format!("{} {}", snapshot.phase, snapshot.done),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Failed(String),
+    Panicked(String),
+    TimedOut(Duration),
+    NoInput(String),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Failed(e) => write!(f, "failed: {e}"),
+            Status::Panicked(e) => write!(f, "panicked: {e}"),
+            Status::TimedOut(limit) => write!(f, "timed out after {limit:.1?}"),
+            Status::NoInput(e) => write!(f, "no input: {e}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub status: Status,
+    /// None when the day has no separate parser or it never finished
+    pub parse_time: Option<Duration>,
+    pub solve_time: Duration,
+}
+
+impl Outcome {
+    pub fn total_time(&self) -> Duration {
+        self.parse_time.unwrap_or_default() + self.solve_time
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(x) = payload.downcast_ref::<&str>() {
+        x.to_string()
+    } else if let Some(x) = payload.downcast_ref::<String>() {
+        x.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Run one solution on its own thread, turning errors, panics and overrunning the
+/// time limit into a status instead of bailing out. On timeout the solver is asked
+/// to stop through its cancel token but not waited for. Progress is shown in the
+/// given style while waiting.
+pub fn run_isolated(
+    solution: &'static Solution,
+    input: &str,
+    options: &Options,
+    timeout: Option<Duration>,
+    style: ProgressStyle,
+) -> Outcome {
+    let cancel = CancelToken::new();
+    let progress = Progress::new();
+    let options = Options {
+        cancel: cancel.clone(),
+        progress: progress.clone(),
+        ..options.clone()
+    };
+    let input = input.to_string();
+    let (sender, receiver) = mpsc::channel();
+
+    let start = Instant::now();
+    let spawned = thread::Builder::new()
+        .name(format!("day{}-part{}", solution.day, solution.part))
+        .stack_size(SOLVER_STACK_SIZE)
+        .spawn(move || {
+            let result: Result<Solved, _> =
+                panic::catch_unwind(AssertUnwindSafe(|| (solution.solve)(&input, &options)));
+            // nobody is listening any more if we ran out of time, that's fine
+            let _ = sender.send(result.map_err(|x| panic_message(x.as_ref())));
+        });
+    if let Err(e) = spawned {
+        return Outcome {
+            day: solution.day,
+            part: solution.part,
+            answer: None,
+            status: Status::Failed(format!("couldn't start solver thread: {e}")),
+            parse_time: None,
+            solve_time: Duration::ZERO,
+        };
+    }
+
+    // wake up for each progress report as well as the deadline
+    let label = format!("day {} part {}", solution.day, solution.part);
+    let mut drawn = false;
+    let result = loop {
+        let remaining = timeout.map(|x| x.saturating_sub(start.elapsed()));
+        let (wait, deadline_next) = match (remaining, style.interval()) {
+            (Some(x), Some(y)) => (Some(x.min(y)), x <= y),
+            (Some(x), None) => (Some(x), true),
+            (None, y) => (y, false),
+        };
+        let received = match wait {
+            Some(wait) => receiver.recv_timeout(wait),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Err(RecvTimeoutError::Timeout) if !deadline_next => {
+                let Some(snapshot) = progress.snapshot() else {
+                    continue;
+                };
+                if style == ProgressStyle::Line {
+                    eprint!("\r\x1b[2K{label}: {}", describe(&snapshot));
+                    let _ = io::stderr().flush();
+                    drawn = true;
+                } else {
+                    info!("{label}: {}", describe(&snapshot));
+                }
+            }
+            x => break x,
+        }
+    };
+    if drawn {
+        eprint!("\r\x1b[2K");
+    }
+    let elapsed = start.elapsed();
+
+    let (result, parse_time, solve_time) = match result {
+        Ok(Ok(solved)) => (Ok(solved.result), solved.parse_time, solved.solve_time),
+        Ok(Err(message)) => (Err(Status::Panicked(message)), None, elapsed),
+        Err(RecvTimeoutError::Timeout) => {
+            cancel.cancel();
+            let limit = timeout.unwrap_or(elapsed);
+            (Err(Status::TimedOut(limit)), None, elapsed)
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            let status = Status::Panicked("solver thread went away".to_string());
+            (Err(status), None, elapsed)
+        }
+    };
+    let (answer, status) = match result {
+        Ok(Ok(answer)) => (Some(answer), Status::Ok),
+        Ok(Err(e)) => (None, Status::Failed(e.to_string())),
+        Err(status) => (None, status),
+    };
+    Outcome {
+        day: solution.day,
+        part: solution.part,
+        answer,
+        status,
+        parse_time,
+        solve_time,
+    }
+}
+
+/// Lay the outcomes out as an aligned plain text table
+pub fn summary_table(outcomes: &[Outcome]) -> String {
+    let rows: Vec<Vec<String>> = outcomes
+        .iter()
+        .map(|x| {
+            vec![
+                x.day.to_string(),
+                x.part.to_string(),
+                x.answer.clone().unwrap_or("-".to_string()),
+                x.status.to_string(),
+                format!("{:.1?}", x.total_time()),
+            ]
+        })
+        .collect();
+    table(&["day", "part", "answer", "status", "time"], &rows)
+}
+
+/// Left-aligned columns two spaces apart, with the header on top
+pub fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let header: Vec<String> = header.iter().map(|x| x.to_string()).collect();
+    let mut widths: Vec<usize> = header.iter().map(|x| x.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        table += cells.join("  ").trim_end();
+        table.push('\n');
+    }
+    table
+}
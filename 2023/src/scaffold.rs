@@ -0,0 +1,252 @@
+use crate::config::Config;
+use std::{
+    env,
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+const TEMPLATE: &str = include_str!("../templates/day.rs");
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    DayOutOfRange(u8),
+    AlreadyExists(PathBuf),
+    AlreadyRegistered(u8),
+    /// lib.rs no longer looks the way the scaffolder expects
+    NoInsertionPoint(&'static str),
+    Io(PathBuf, std::io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::DayOutOfRange(day) => write!(f, "day {day} is not between 1 and 25"),
+            ScaffoldError::AlreadyExists(path) => {
+                write!(
+                    f,
+                    "{} already exists, refusing to overwrite",
+                    path.display()
+                )
+            }
+            ScaffoldError::AlreadyRegistered(day) => {
+                write!(f, "day {day} is already registered in lib.rs")
+            }
+            ScaffoldError::NoInsertionPoint(what) => {
+                write!(f, "couldn't find where to add {what} in lib.rs")
+            }
+            ScaffoldError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl Error for ScaffoldError {}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|e| ScaffoldError::Io(path.to_owned(), e))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    fs::write(path, contents).map_err(|e| ScaffoldError::Io(path.to_owned(), e))
+}
+
+// New modules go after the last day module, new solutions at the end of SOLUTIONS
+fn register(lib: &str, day: u8) -> Result<String, ScaffoldError> {
+    let module = format!("pub mod day{day:02};");
+    if lib.lines().any(|x| x == module) {
+        return Err(ScaffoldError::AlreadyRegistered(day));
+    }
+    let mods_end = lib
+        .match_indices("pub mod day")
+        .last()
+        .and_then(|(i, _)| lib[i..].find('\n').map(|x| i + x + 1))
+        .ok_or(ScaffoldError::NoInsertionPoint("the module"))?;
+    let solutions_start = lib
+        .find("pub const SOLUTIONS")
+        .ok_or(ScaffoldError::NoInsertionPoint("the solutions"))?;
+    let solutions_end = lib[solutions_start..]
+        .find("\n];")
+        .map(|x| solutions_start + x + 1)
+        .ok_or(ScaffoldError::NoInsertionPoint("the solutions"))?;
+
+    let mut entries = String::new();
+    for part in 1..=2 {
+        entries += &format!(
+            "    Solution {{\n        day: {day},\n        part: {part},\n        \
+             solve: |input, _| timed(input, day{day:02}::get_day{day}_input, \
+             day{day:02}::day{day}_p{part}),\n    \
+             }},\n"
+        );
+    }
+    Ok([
+        &lib[..mods_end],
+        &format!("{module}\n"),
+        &lib[mods_end..solutions_end],
+        &entries,
+        &lib[solutions_end..],
+    ]
+    .concat())
+}
+
+// The module's tests include the example from src/, so point at it from there when
+// it's inside the crate
+fn include_path(root: &Path, path: &Path) -> String {
+    let path = env::current_dir().map_or(path.to_owned(), |x| x.join(path));
+    match path.strip_prefix(root) {
+        Ok(x) => format!("../{}", x.display()),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+/// Create the module, tests and input placeholders for a new day and register it,
+/// putting the placeholders wherever `config` says inputs and examples live.
+/// Returns the files created.
+pub fn new_day(root: &Path, config: &Config, day: u8) -> Result<Vec<PathBuf>, ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::DayOutOfRange(day));
+    }
+    let module = root.join(format!("src/day{day:02}.rs"));
+    if module.exists() {
+        return Err(ScaffoldError::AlreadyExists(module));
+    }
+    let lib_path = root.join("src/lib.rs");
+    // check everything before touching the tree so a refusal leaves nothing behind
+    let lib = register(&read(&lib_path)?, day)?;
+
+    let mut created = vec![];
+    let placeholders = (1..=2).flat_map(|part| {
+        [
+            config.input_path(day, part),
+            config.example_path(day, part, 1),
+        ]
+    });
+    for path in placeholders {
+        // inputs may already have been downloaded, leave them be. Patterns without
+        // {part} give the same file for both parts.
+        if !path.exists() && !created.contains(&path) {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| ScaffoldError::Io(dir.to_owned(), e))?;
+            }
+            write(&path, "")?;
+            created.push(path);
+        }
+    }
+    let example = include_path(root, &config.example_path(day, 1, 1));
+    let source = TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{example}", &example);
+    write(&module, &source)?;
+    created.push(module);
+    write(&lib_path, &lib)?;
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::scratch_dir;
+
+    const LIB: &str = "\
+pub mod day01;
+pub mod day02;
+pub mod parse;
+
+pub const SOLUTIONS: &[Solution] = &[
+    Solution {
+        day: 1,
+        part: 1,
+        solve: |input, _| timed_whole(|| day01::day1_p1(input)),
+    },
+];
+
+pub fn find_solution() {}
+";
+
+    #[test]
+    fn module_and_entries_go_after_the_existing_ones() {
+        let lib = register(LIB, 3).unwrap();
+        assert!(lib.contains("\npub mod day02;\npub mod day03;\npub mod parse;\n"));
+        let entries = "\
+        solve: |input, _| timed_whole(|| day01::day1_p1(input)),
+    },
+    Solution {
+        day: 3,
+        part: 1,
+        solve: |input, _| timed(input, day03::get_day3_input, day03::day3_p1),
+    },
+    Solution {
+        day: 3,
+        part: 2,
+        solve: |input, _| timed(input, day03::get_day3_input, day03::day3_p2),
+    },
+];
+
+pub fn find_solution() {}
+";
+        assert!(lib.ends_with(entries), "{lib}");
+    }
+
+    #[test]
+    fn registered_day_is_refused() {
+        let lib = register(LIB, 3).unwrap();
+        assert!(matches!(
+            register(&lib, 3),
+            Err(ScaffoldError::AlreadyRegistered(3))
+        ));
+        assert!(matches!(
+            register(LIB, 2),
+            Err(ScaffoldError::AlreadyRegistered(2))
+        ));
+    }
+
+    #[test]
+    fn missing_anchors_are_errors() {
+        let no_solutions = LIB.replace("pub const SOLUTIONS", "const ANSWERS");
+        assert!(matches!(
+            register(&no_solutions, 3),
+            Err(ScaffoldError::NoInsertionPoint("the solutions"))
+        ));
+        let unterminated = LIB.replace("];", "]");
+        assert!(matches!(
+            register(&unterminated, 3),
+            Err(ScaffoldError::NoInsertionPoint("the solutions"))
+        ));
+        let no_days = LIB.replace("pub mod day0", "pub mod other0");
+        assert!(matches!(
+            register(&no_days, 3),
+            Err(ScaffoldError::NoInsertionPoint("the module"))
+        ));
+    }
+
+    #[test]
+    fn placeholders_follow_the_config() {
+        let root = scratch_dir("new-day");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        let config_path = root.join("aoc.toml");
+        fs::write(
+            &config_path,
+            "input_dir = \"puzzles\"\n\
+             input_pattern = \"{day}.in\"\n\
+             example_pattern = \"{day}-{part}.ex{n}\"\n",
+        )
+        .unwrap();
+        let config = Config::load(&config_path).unwrap();
+
+        let created = new_day(&root, &config, 15).unwrap();
+        let expected: Vec<PathBuf> = ["puzzles/15.in", "puzzles/15-1.ex", "puzzles/15-2.ex"]
+            .iter()
+            .map(|x| root.join(x))
+            .chain([root.join("src/day15.rs")])
+            .collect();
+        assert_eq!(created, expected);
+        let module = fs::read_to_string(root.join("src/day15.rs")).unwrap();
+        assert!(
+            module.contains("include_str!(\"../puzzles/15-1.ex\")"),
+            "{module}"
+        );
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("pub mod day15;"));
+    }
+}
@@ -0,0 +1,495 @@
+use crate::client::{Client, ClientError};
+use num::BigInt;
+use std::{
+    error::Error,
+    fmt, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// Submitted too soon after the last attempt, nothing was judged
+    Wait,
+    /// The part is already solved, or part 1 isn't yet
+    WrongLevel,
+}
+
+impl Verdict {
+    fn from_response(text: &str) -> Option<Verdict> {
+        if text.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if text.contains("too high") {
+            Some(Verdict::TooHigh)
+        } else if text.contains("too low") {
+            Some(Verdict::TooLow)
+        } else if text.contains("That's not the right answer") {
+            Some(Verdict::Wrong)
+        } else if text.contains("You gave an answer too recently") {
+            Some(Verdict::Wait)
+        } else if text.contains("You don't seem to be solving the right level") {
+            Some(Verdict::WrongLevel)
+        } else {
+            None
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+            Verdict::Wait => "wait",
+            Verdict::WrongLevel => "wrong-level",
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Verdict::Correct,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+            Verdict::Wrong,
+            Verdict::Wait,
+            Verdict::WrongLevel,
+        ]
+        .into_iter()
+        .find(|x| x.as_str() == s)
+        .ok_or(format!("unknown verdict {s:?}"))
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub timestamp: u64,
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    AlreadySolved(String),
+    KnownWrong(String),
+    /// The answer is at or above something already judged too high
+    AboveBound {
+        answer: String,
+        bound: String,
+    },
+    /// The answer is at or below something already judged too low
+    BelowBound {
+        answer: String,
+        bound: String,
+    },
+    UnrecognisedResponse(String),
+    CorruptLog {
+        line: usize,
+        text: String,
+    },
+    Client(ClientError),
+    Io(PathBuf, std::io::Error),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::AlreadySolved(answer) => {
+                write!(f, "already solved, the accepted answer was {answer}")
+            }
+            SubmitError::KnownWrong(answer) => {
+                write!(f, "{answer} has already been submitted and was wrong")
+            }
+            SubmitError::AboveBound { answer, bound } => {
+                write!(f, "{answer} can't be right, {bound} was already too high")
+            }
+            SubmitError::BelowBound { answer, bound } => {
+                write!(f, "{answer} can't be right, {bound} was already too low")
+            }
+            SubmitError::UnrecognisedResponse(text) => {
+                write!(f, "couldn't make sense of the response: {text}")
+            }
+            SubmitError::CorruptLog { line, text } => {
+                write!(f, "answer log line {line} is malformed: {text:?}")
+            }
+            SubmitError::Client(e) => write!(f, "{e}"),
+            SubmitError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl Error for SubmitError {}
+
+impl From<ClientError> for SubmitError {
+    fn from(e: ClientError) -> Self {
+        SubmitError::Client(e)
+    }
+}
+
+/// Every submission we've made, one tab separated line each
+pub struct AnswerLog {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl AnswerLog {
+    pub fn open(path: &Path) -> Result<AnswerLog, SubmitError> {
+        let text = match fs::read_to_string(path) {
+            Ok(x) => x,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(SubmitError::Io(path.to_owned(), e)),
+        };
+        let attempts = text
+            .lines()
+            .enumerate()
+            .filter(|(_, x)| !x.is_empty())
+            .map(|(i, line)| {
+                parse_attempt(line).ok_or(SubmitError::CorruptLog {
+                    line: i + 1,
+                    text: line.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(AnswerLog {
+            path: path.to_owned(),
+            attempts,
+        })
+    }
+
+    fn attempts(&self, year: u16, day: u8, part: u8) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |x| x.year == year && x.day == day && x.part == part)
+    }
+
+    /// Refuse anything the log already tells us can't be the answer
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<(), SubmitError> {
+        let attempts: Vec<_> = self.attempts(year, day, part).collect();
+        if let Some(x) = attempts.iter().find(|x| x.verdict == Verdict::Correct) {
+            return Err(SubmitError::AlreadySolved(x.answer.clone()));
+        }
+        let judged_wrong = [Verdict::TooHigh, Verdict::TooLow, Verdict::Wrong];
+        if attempts
+            .iter()
+            .any(|x| x.answer == answer && judged_wrong.contains(&x.verdict))
+        {
+            return Err(SubmitError::KnownWrong(answer.to_string()));
+        }
+
+        // bounds only make sense for numeric answers
+        let Ok(value) = answer.parse::<BigInt>() else {
+            return Ok(());
+        };
+        let bounds = |verdict| {
+            attempts
+                .iter()
+                .filter(move |x| x.verdict == verdict)
+                .filter_map(|x| x.answer.parse::<BigInt>().ok())
+        };
+        if let Some(bound) = bounds(Verdict::TooHigh).filter(|x| &value >= x).min() {
+            return Err(SubmitError::AboveBound {
+                answer: answer.to_string(),
+                bound: bound.to_string(),
+            });
+        }
+        if let Some(bound) = bounds(Verdict::TooLow).filter(|x| &value <= x).max() {
+            return Err(SubmitError::BelowBound {
+                answer: answer.to_string(),
+                bound: bound.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), SubmitError> {
+        let io_error = |e| SubmitError::Io(self.path.clone(), e);
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(io_error)?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            attempt.timestamp,
+            attempt.year,
+            attempt.day,
+            attempt.part,
+            attempt.verdict,
+            attempt.answer
+        )
+        .map_err(io_error)?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+fn parse_attempt(line: &str) -> Option<Attempt> {
+    let mut fields = line.splitn(6, '\t');
+    Some(Attempt {
+        timestamp: fields.next()?.parse().ok()?,
+        year: fields.next()?.parse().ok()?,
+        day: fields.next()?.parse().ok()?,
+        part: fields.next()?.parse().ok()?,
+        verdict: fields.next()?.parse().ok()?,
+        answer: fields.next()?.to_string(),
+    })
+}
+
+// The verdict is the first paragraph of the page's <article>
+fn response_text(body: &str) -> String {
+    let article = body
+        .split_once("<article>")
+        .and_then(|(_, x)| x.split_once("</article>"))
+        .map_or(body, |(x, _)| x);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Post an answer unless the log rules it out, and record the verdict.
+/// Returns the verdict along with the server's explanation.
+pub fn submit(
+    client: &Client,
+    log: &mut AnswerLog,
+    day: u8,
+    part: u8,
+    answer: &str,
+) -> Result<(Verdict, String), SubmitError> {
+    log.check(client.year(), day, part, answer)?;
+    let level = part.to_string();
+    let body = client.post(day, "/answer", &[("level", &level), ("answer", answer)])?;
+    let text = response_text(&body);
+    let verdict = Verdict::from_response(&text)
+        .ok_or_else(|| SubmitError::UnrecognisedResponse(text.clone()))?;
+    log.record(Attempt {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs()),
+        year: client.year(),
+        day,
+        part,
+        verdict,
+        answer: answer.to_string(),
+    })?;
+    Ok((verdict, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{scratch_dir, serve};
+    use std::env;
+    use std::time::Duration;
+
+    fn verdict(body: &str) -> Option<Verdict> {
+        Verdict::from_response(&response_text(body))
+    }
+
+    #[test]
+    fn responses_are_recognised() {
+        let page = |x: &str| format!("<html><main><article><p>{x}</p></article></main></html>");
+        assert_eq!(
+            verdict(&page(
+                "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer."
+            )),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(
+            verdict(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck, ..."
+            )),
+            Some(Verdict::TooHigh)
+        );
+        assert_eq!(
+            verdict(&page(
+                "That's not the right answer; your answer is too low.  If you're stuck, ..."
+            )),
+            Some(Verdict::TooLow)
+        );
+        assert_eq!(
+            verdict(&page("That's not the right answer.  If you're stuck, ...")),
+            Some(Verdict::Wrong)
+        );
+        assert_eq!(
+            verdict(&page(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 39s left to wait. <a href=\"/2023/day/1\">[Return to Day 1]</a>"
+            )),
+            Some(Verdict::Wait)
+        );
+        assert_eq!(
+            verdict(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Some(Verdict::WrongLevel)
+        );
+        assert_eq!(verdict(&page("Something else entirely")), None);
+    }
+
+    fn attempt(day: u8, part: u8, verdict: Verdict, answer: &str) -> Attempt {
+        Attempt {
+            timestamp: 0,
+            year: 2023,
+            day,
+            part,
+            verdict,
+            answer: answer.to_string(),
+        }
+    }
+
+    fn log(attempts: Vec<Attempt>) -> AnswerLog {
+        AnswerLog {
+            path: PathBuf::new(),
+            attempts,
+        }
+    }
+
+    #[test]
+    fn known_wrong_answers_are_refused() {
+        let log = log(vec![
+            attempt(1, 1, Verdict::Wrong, "abc"),
+            attempt(1, 1, Verdict::TooHigh, "500"),
+        ]);
+        assert!(matches!(
+            log.check(2023, 1, 1, "abc"),
+            Err(SubmitError::KnownWrong(x)) if x == "abc"
+        ));
+        assert!(matches!(
+            log.check(2023, 1, 1, "500"),
+            Err(SubmitError::KnownWrong(_))
+        ));
+        // other parts and days are judged separately
+        assert!(log.check(2023, 1, 2, "abc").is_ok());
+        assert!(log.check(2023, 2, 1, "500").is_ok());
+        assert!(log.check(2023, 1, 1, "abd").is_ok());
+    }
+
+    #[test]
+    fn answers_outside_the_bounds_are_refused() {
+        let log = log(vec![
+            attempt(1, 1, Verdict::TooHigh, "500"),
+            attempt(1, 1, Verdict::TooHigh, "400"),
+            attempt(1, 1, Verdict::TooLow, "100"),
+            attempt(1, 1, Verdict::Wait, "250"),
+        ]);
+        assert!(matches!(
+            log.check(2023, 1, 1, "450"),
+            Err(SubmitError::AboveBound { bound, .. }) if bound == "400"
+        ));
+        assert!(matches!(
+            log.check(2023, 1, 1, "400"),
+            Err(SubmitError::KnownWrong(_))
+        ));
+        assert!(matches!(
+            log.check(2023, 1, 1, "99"),
+            Err(SubmitError::BelowBound { bound, .. }) if bound == "100"
+        ));
+        // a wait wasn't judged, so it doesn't rule anything out
+        assert!(log.check(2023, 1, 1, "250").is_ok());
+        assert!(log.check(2023, 1, 1, "101").is_ok());
+        assert!(log.check(2023, 1, 1, "399").is_ok());
+        assert!(log.check(2023, 1, 1, "not a number").is_ok());
+    }
+
+    #[test]
+    fn solved_parts_are_refused() {
+        let log = log(vec![attempt(1, 1, Verdict::Correct, "42")]);
+        assert!(matches!(
+            log.check(2023, 1, 1, "43"),
+            Err(SubmitError::AlreadySolved(x)) if x == "42"
+        ));
+    }
+
+    #[test]
+    fn log_round_trips_through_its_file() {
+        let path = env::temp_dir().join(format!("aoc-answers-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut log = AnswerLog::open(&path).unwrap();
+        log.record(attempt(3, 2, Verdict::TooLow, "12 34")).unwrap();
+        let reopened = AnswerLog::open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(reopened.attempts.len(), 1);
+        assert_eq!(reopened.attempts[0].verdict, Verdict::TooLow);
+        assert_eq!(reopened.attempts[0].answer, "12 34");
+        assert!(matches!(
+            reopened.check(2023, 3, 2, "12 34"),
+            Err(SubmitError::KnownWrong(_))
+        ));
+    }
+
+    const CORRECT_PAGE: &str = "<html><main><article><p>That's the right answer!  \
+        You are <span class=\"day-success\">one gold star</span> closer.</p></article></main></html>";
+
+    #[test]
+    fn submission_is_posted_and_logged() {
+        let (base_url, requests) = serve(vec![(200, CORRECT_PAGE)]);
+        let dir = scratch_dir("submit");
+        let client = Client::new(&base_url, "token".to_string(), 2023, dir.clone());
+        let path = dir.join("answers.tsv");
+        let mut log = AnswerLog::open(&path).unwrap();
+
+        let (verdict, text) = submit(&client, &mut log, 4, 2, "1234").unwrap();
+        assert_eq!(verdict, Verdict::Correct);
+        assert!(text.starts_with("That's the right answer!"), "{text}");
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2023/day/4/answer "), "{request}");
+        assert!(
+            request.ends_with("\r\n\r\nlevel=2&answer=1234"),
+            "{request}"
+        );
+
+        let logged = fs::read_to_string(&path).unwrap();
+        let fields: Vec<&str> = logged.trim_end().split('\t').collect();
+        assert_eq!(fields[1..], ["2023", "4", "2", "correct", "1234"]);
+        assert_eq!(log.attempts.len(), 1);
+    }
+
+    #[test]
+    fn solved_part_is_not_posted_again() {
+        let (base_url, requests) = serve(vec![(200, CORRECT_PAGE)]);
+        let dir = scratch_dir("resubmit");
+        let client = Client::new(&base_url, "token".to_string(), 2023, dir.clone());
+        let path = dir.join("answers.tsv");
+        let mut log = AnswerLog::open(&path).unwrap();
+        log.record(attempt(4, 1, Verdict::Correct, "1234")).unwrap();
+        let before = fs::read_to_string(&path).unwrap();
+
+        assert!(matches!(
+            submit(&client, &mut log, 4, 1, "1234"),
+            Err(SubmitError::AlreadySolved(x)) if x == "1234"
+        ));
+        assert!(requests.recv_timeout(Duration::from_millis(200)).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    }
+}
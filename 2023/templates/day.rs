@@ -0,0 +1,33 @@
+use crate::parse::{self, ParseError};
+use aoc_core::SolveResult;
+
+pub(crate) fn get_day{day}_input(input: &str) -> Result<Vec<&str>, ParseError> {
+    parse::lines(input, Ok)
+}
+
+pub fn day{day}_p1(input: Vec<&str>) -> SolveResult {
+    Err(format!("day {day} part 1 is not solved yet ({} lines)", input.len()).into())
+}
+
+pub fn day{day}_p2(input: Vec<&str>) -> SolveResult {
+    Err(format!("day {day} part 2 is not solved yet ({} lines)", input.len()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("{example}");
+
+    #[test]
+    #[ignore = "fill in the example answer"]
+    fn p1_example() {
+        assert_eq!(day{day}_p1(get_day{day}_input(EXAMPLE).unwrap()).unwrap(), "");
+    }
+
+    #[test]
+    #[ignore = "fill in the example answer"]
+    fn p2_example() {
+        assert_eq!(day{day}_p2(get_day{day}_input(EXAMPLE).unwrap()).unwrap(), "");
+    }
+}
@@ -0,0 +1,101 @@
+use crate::grid::Grid;
+
+const WORD_BITS: usize = 64;
+
+/// Binary grid packed into `u64` words once per row and once per column, so whole rows or
+/// columns can be compared with XOR and popcount instead of cell by cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+}
+
+fn words_for(bits: usize) -> usize {
+    bits.div_ceil(WORD_BITS)
+}
+
+fn xor_count(a: &[u64], b: &[u64]) -> u32 {
+    a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+fn xor_positions(a: &[u64], b: &[u64]) -> Vec<usize> {
+    let mut positions = vec![];
+    for (word_idx, (x, y)) in a.iter().zip(b).enumerate() {
+        let mut diff = x ^ y;
+        // pop the lowest set bit until none are left
+        while diff != 0 {
+            positions.push(word_idx * WORD_BITS + diff.trailing_zeros() as usize);
+            diff &= diff - 1;
+        }
+    }
+    positions
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let row_words = words_for(width);
+        let col_words = words_for(height);
+        let mut rows = vec![0; height * row_words];
+        let mut cols = vec![0; width * col_words];
+        for (point, &cell) in grid.iter() {
+            let (r, c) = (point.row, point.col);
+            if cell {
+                rows[r * row_words + c / WORD_BITS] |= 1 << (c % WORD_BITS);
+                cols[c * col_words + r / WORD_BITS] |= 1 << (r % WORD_BITS);
+            }
+        }
+        BitGrid {
+            width,
+            height,
+            rows,
+            cols,
+        }
+    }
+}
+
+impl BitGrid {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.rows[row * words_for(self.width) + col / WORD_BITS] >> (col % WORD_BITS) & 1 == 1
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        let row_words = words_for(self.width);
+        &self.rows[row * row_words..(row + 1) * row_words]
+    }
+
+    fn col(&self, col: usize) -> &[u64] {
+        let col_words = words_for(self.height);
+        &self.cols[col * col_words..(col + 1) * col_words]
+    }
+
+    /// Number of cells that differ between two rows
+    pub fn row_differences(&self, a: usize, b: usize) -> u32 {
+        xor_count(self.row(a), self.row(b))
+    }
+
+    /// Number of cells that differ between two columns
+    pub fn col_differences(&self, a: usize, b: usize) -> u32 {
+        xor_count(self.col(a), self.col(b))
+    }
+
+    /// Column indices where two rows differ
+    pub fn row_mismatches(&self, a: usize, b: usize) -> Vec<usize> {
+        xor_positions(self.row(a), self.row(b))
+    }
+
+    /// Row indices where two columns differ
+    pub fn col_mismatches(&self, a: usize, b: usize) -> Vec<usize> {
+        xor_positions(self.col(a), self.col(b))
+    }
+}
@@ -0,0 +1,78 @@
+use std::convert::Infallible;
+
+/// Where a sequence of repeated steps starts looping, and how long the loop is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Smallest number of steps that reaches the same state as taking `steps` steps
+    pub fn equivalent_steps(&self, steps: usize) -> usize {
+        if steps < self.start {
+            steps
+        } else {
+            self.start + (steps - self.start) % self.length
+        }
+    }
+}
+
+/// Brent's cycle detection: only ever holds two states at a time, at the cost of
+/// re-running the step function a few extra times
+/// https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm
+pub fn brent<T: Clone + PartialEq>(initial: &T, mut step: impl FnMut(&mut T)) -> Cycle {
+    let result: Result<Cycle, Infallible> = try_brent(initial, |x| {
+        step(x);
+        Ok(())
+    });
+    let Ok(cycle) = result;
+    cycle
+}
+
+/// Brent's algorithm with a fallible step, stopping at the first error. Useful when
+/// the sequence might never loop and the caller needs a way out.
+pub fn try_brent<T: Clone + PartialEq, E>(
+    initial: &T,
+    step: impl FnMut(&mut T) -> Result<(), E>,
+) -> Result<Cycle, E> {
+    try_brent_at_start(initial, step).map(|(cycle, _)| cycle)
+}
+
+/// `try_brent`, also handing back the state the loop starts from so the caller can
+/// carry on from there rather than stepping through the lead-in again
+pub fn try_brent_at_start<T: Clone + PartialEq, E>(
+    initial: &T,
+    mut step: impl FnMut(&mut T) -> Result<(), E>,
+) -> Result<(Cycle, T), E> {
+    // find the loop length by moving the tortoise up to the hare at each power of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    step(&mut hare)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        step(&mut hare)?;
+        length += 1;
+    }
+
+    // then walk both from the start, one loop length apart, until they meet at the loop start
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        step(&mut hare)?;
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        step(&mut tortoise)?;
+        step(&mut hare)?;
+        start += 1;
+    }
+
+    Ok((Cycle { start, length }, tortoise))
+}
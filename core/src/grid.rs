@@ -0,0 +1,273 @@
+use crate::point::{Direction, Point};
+use std::{
+    error::Error,
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+/// Rectangular grid stored row-major in a single flat buffer, indexed by `Point`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseGridError<E> {
+    /// A row had a different length to the first row
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A character couldn't be converted into a cell
+    Cell { row: usize, col: usize, error: E },
+}
+
+impl<E: fmt::Display> fmt::Display for ParseGridError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseGridError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {row} has {found} cells, expected {expected}"),
+            ParseGridError::Cell { row, col, error } => {
+                write!(f, "bad cell at row {row}, col {col}: {error}")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> Error for ParseGridError<E> {}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "grid of {width}x{height} needs {} cells",
+            width * height
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Build from nested rows, which must all be the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |x| x.len());
+        if let Some(row) = rows.iter().position(|x| x.len() != width) {
+            panic!("row {row} has {} cells, expected {width}", rows[row].len());
+        }
+        Grid::new(width, height, rows.into_iter().flatten().collect())
+    }
+
+    /// Parse one row per line, converting each character with `parse_cell`
+    pub fn parse_with<E>(
+        s: &str,
+        mut parse_cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, ParseGridError<E>> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for (row, line) in s.lines().enumerate() {
+            let mut found = 0;
+            for (col, c) in line.chars().enumerate() {
+                let cell =
+                    parse_cell(c).map_err(|error| ParseGridError::Cell { row, col, error })?;
+                cells.push(cell);
+                found += 1;
+            }
+            let expected = *width.get_or_insert(found);
+            if found != expected {
+                return Err(ParseGridError::Ragged {
+                    row,
+                    expected,
+                    found,
+                });
+            }
+            height += 1;
+        }
+        Ok(Grid::new(width.unwrap_or(0), height, cells))
+    }
+
+    /// Parse one row per line using the cell type's own conversion from `char`
+    pub fn parse(s: &str) -> Result<Self, ParseGridError<T::Error>>
+    where
+        T: TryFrom<char>,
+    {
+        Grid::parse_with(s, T::try_from)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        point.row < self.height && point.col < self.width
+    }
+
+    pub fn get(&self, point: &Point) -> Option<&T> {
+        if self.contains(point) {
+            Some(&self.cells[point.row * self.width + point.col])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        if self.contains(point) {
+            Some(&mut self.cells[point.row * self.width + point.col])
+        } else {
+            None
+        }
+    }
+
+    /// The flat row-major buffer
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// Every cell with its position, in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(idx, x)| (Point::new(idx / self.width, idx % self.width), x))
+    }
+
+    /// One step in the given direction, if it's still inside the grid
+    pub fn step(&self, point: &Point, direction: Direction) -> Option<Point> {
+        point.step(direction).filter(move |x| self.contains(x))
+    }
+
+    /// Orthogonal neighbours inside the grid
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbours4().filter(move |x| self.contains(x))
+    }
+
+    /// Orthogonal and diagonal neighbours inside the grid
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbours8().filter(move |x| self.contains(x))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a zero width, but then there are no cells to split anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    // build a new grid of the given shape, pulling each (row, col) from a source position
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> usize) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| self.cells[source(row, col)].clone())
+            .collect();
+        Grid::new(width, height, cells)
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |row, col| col * self.width + row)
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |row, col| {
+            (self.height - 1 - col) * self.width + row
+        })
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |row, col| {
+            col * self.width + (self.width - 1 - row)
+        })
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.width, self.height, |row, col| {
+            row * self.width + (self.width - 1 - col)
+        })
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.width, self.height, |row, col| {
+            (self.height - 1 - row) * self.width + col
+        })
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(&point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(&point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
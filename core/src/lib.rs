@@ -0,0 +1,17 @@
+use std::error::Error;
+
+pub mod bitgrid;
+pub mod cancel;
+pub mod cycle;
+pub mod grid;
+pub mod parse;
+pub mod point;
+pub mod progress;
+pub mod rng;
+
+/// Solver answers are whatever the puzzle asks for, rendered as text
+pub type Answer = String;
+
+pub type SolveError = Box<dyn Error + Send + Sync>;
+
+pub type SolveResult = Result<Answer, SolveError>;
@@ -0,0 +1,201 @@
+use crate::grid::{Grid, ParseGridError};
+use std::{any::type_name, convert::Infallible, error::Error, fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Text couldn't be converted into the expected type
+    InvalidValue { text: String, expected: String },
+    /// A delimiter that splits the text in two wasn't there
+    MissingDelimiter { delimiter: String, text: String },
+    /// No section with this label in the input
+    MissingSection { label: String },
+    /// Wrong number of items, e.g. a mapping line without exactly three numbers
+    WrongCount { expected: usize, found: usize },
+}
+
+/// Error from any of the parsing helpers, with the 1-based line it happened on when known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind) -> Self {
+        ParseError { line: None, kind }
+    }
+
+    pub fn invalid(text: &str, expected: &str) -> Self {
+        ParseError::new(ParseErrorKind::InvalidValue {
+            text: text.to_string(),
+            expected: expected.to_string(),
+        })
+    }
+
+    /// Attach a line number, keeping the innermost one if already set
+    pub fn at_line(self, line: usize) -> Self {
+        ParseError {
+            line: self.line.or(Some(line)),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorKind::InvalidValue { text, expected } => {
+                write!(f, "expected {expected} but found {text:?}")
+            }
+            ParseErrorKind::MissingDelimiter { delimiter, text } => {
+                write!(f, "expected {delimiter:?} in {text:?}")
+            }
+            ParseErrorKind::MissingSection { label } => write!(f, "no section labelled {label:?}"),
+            ParseErrorKind::WrongCount { expected, found } => {
+                write!(f, "expected {expected} items but found {found}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+// lets infallible cell conversions like `char` itself go through `grid`
+impl From<Infallible> for ParseError {
+    fn from(x: Infallible) -> Self {
+        match x {}
+    }
+}
+
+/// Parse a single trimmed value with its `FromStr`
+pub fn value<T: FromStr>(text: &str) -> Result<T, ParseError> {
+    let text = text.trim();
+    text.parse()
+        .map_err(|_| ParseError::invalid(text, type_name::<T>()))
+}
+
+/// Whitespace-separated numbers like "79 14 55 13"
+pub fn ints<T: FromStr>(text: &str) -> Result<Vec<T>, ParseError> {
+    text.split_ascii_whitespace().map(value).collect()
+}
+
+/// Numbers split on a separator like "1,1,3"
+pub fn ints_sep<T: FromStr>(text: &str, separator: &str) -> Result<Vec<T>, ParseError> {
+    text.split(separator).map(value).collect()
+}
+
+/// Split around the first `delimiter`, failing if it isn't there
+pub fn split_pair<'a>(text: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+    text.split_once(delimiter).ok_or_else(|| {
+        ParseError::new(ParseErrorKind::MissingDelimiter {
+            delimiter: delimiter.to_string(),
+            text: text.to_string(),
+        })
+    })
+}
+
+/// A `key <delimiter> value` line like "Game 1: 3 blue" or "AAA = (BBB, CCC)", both sides trimmed
+pub fn key_value<'a>(line: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+    let (key, value) = split_pair(line, delimiter)?;
+    Ok((key.trim(), value.trim()))
+}
+
+/// Every non-blank line as a key/value pair
+pub fn key_values<'a>(
+    text: &'a str,
+    delimiter: &str,
+) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    lines(text, |l| key_value(l, delimiter))
+}
+
+/// Strip surrounding `open` and `close` markers, like the brackets in "(BBB, CCC)"
+pub fn delimited<'a>(text: &'a str, open: &str, close: &str) -> Result<&'a str, ParseError> {
+    let text = text.trim();
+    text.strip_prefix(open)
+        .and_then(|x| x.strip_suffix(close))
+        .ok_or_else(|| ParseError::invalid(text, &format!("text wrapped in {open}...{close}")))
+}
+
+/// Run `parse_line` over every non-blank line, tagging any error with its line number
+pub fn lines<'a, T>(
+    text: &'a str,
+    mut parse_line: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(idx, l)| parse_line(l).map_err(|e| e.at_line(idx + 1)))
+        .collect()
+}
+
+/// Blocks of lines separated by blank lines
+pub fn blocks(text: &str) -> Vec<&str> {
+    text.split("\n\n")
+        .map(|x| x.trim_matches('\n'))
+        .filter(|x| !x.is_empty())
+        .collect()
+}
+
+/// One grid row per line, each character converted with the cell type's `TryFrom<char>`
+pub fn grid<T>(text: &str) -> Result<Grid<T>, ParseError>
+where
+    T: TryFrom<char>,
+    ParseError: From<T::Error>,
+{
+    Grid::parse(text).map_err(|e| match e {
+        ParseGridError::Ragged {
+            row,
+            expected,
+            found,
+        } => ParseError::new(ParseErrorKind::WrongCount { expected, found }).at_line(row + 1),
+        ParseGridError::Cell { row, error, .. } => ParseError::from(error).at_line(row + 1),
+    })
+}
+
+/// Collect exactly `N` items into an array
+pub fn array<T, const N: usize>(items: Vec<T>) -> Result<[T; N], ParseError> {
+    let found = items.len();
+    items
+        .try_into()
+        .map_err(|_| ParseError::new(ParseErrorKind::WrongCount { expected: N, found }))
+}
+
+/// Blank-line-separated blocks that each start with `label:`, like the maps in an almanac
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sections<'a>(Vec<(&'a str, &'a str)>);
+
+impl<'a> Sections<'a> {
+    /// Body of the section with this label: anything after the colon plus the following lines
+    pub fn get(&self, label: &str) -> Result<&'a str, ParseError> {
+        self.0
+            .iter()
+            .find(|(x, _)| *x == label)
+            .map(|(_, body)| *body)
+            .ok_or_else(|| {
+                ParseError::new(ParseErrorKind::MissingSection {
+                    label: label.to_string(),
+                })
+            })
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.0.iter().map(|(label, _)| *label)
+    }
+}
+
+pub fn sections(text: &str) -> Result<Sections<'_>, ParseError> {
+    blocks(text)
+        .into_iter()
+        .map(|x| key_value(x, ":"))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Sections)
+}
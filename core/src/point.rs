@@ -0,0 +1,131 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// Position on a grid, with rows counting down from the top and cols counting right from the left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+const DIAGONALS: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, 1), (1, -1)];
+
+impl Point {
+    pub fn new(row: usize, col: usize) -> Self {
+        Point { row, col }
+    }
+
+    /// Point shifted by (rows, cols), or `None` if that would go below zero
+    pub fn offset(&self, (rows, cols): (isize, isize)) -> Option<Point> {
+        Some(Point {
+            row: self.row.checked_add_signed(rows)?,
+            col: self.col.checked_add_signed(cols)?,
+        })
+    }
+
+    /// One step in the given direction, or `None` if that would go off the top or left edge
+    pub fn step(&self, direction: Direction) -> Option<Point> {
+        self.offset(direction.offset())
+    }
+
+    pub fn manhattan(&self, other: &Point) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+
+    /// Orthogonal neighbours that don't go below zero
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.iter().filter_map(move |x| self.step(*x))
+    }
+
+    /// Orthogonal and diagonal neighbours that don't go below zero
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        self.neighbours4()
+            .chain(DIAGONALS.iter().filter_map(move |x| self.offset(*x)))
+    }
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// (rows, cols) moved by one step
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    pub fn rotate_clockwise(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Direction {
+        self.rotate_clockwise().opposite()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseDirectionError(char);
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected 'N', 'E', 'S' or 'W' but found {:?}", self.0)
+    }
+}
+
+impl Error for ParseDirectionError {}
+
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'N' => Ok(Direction::North),
+            'E' => Ok(Direction::East),
+            'S' => Ok(Direction::South),
+            'W' => Ok(Direction::West),
+            _ => Err(ParseDirectionError(value)),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c),
+            (Some(_), Some(c)) => Err(ParseDirectionError(c)),
+            (None, _) => Err(ParseDirectionError(' ')),
+        }
+    }
+}
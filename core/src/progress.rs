@@ -0,0 +1,57 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+#[derive(Debug, Default)]
+struct State {
+    phase: Mutex<Option<(String, Option<u64>)>>,
+    done: AtomicU64,
+}
+
+/// Where a solver is up to. Solvers report into it, the runner reads snapshots of it
+/// from another thread and decides how to show them. Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct Progress(Arc<State>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub phase: String,
+    pub done: u64,
+    pub total: Option<u64>,
+}
+
+impl Snapshot {
+    pub fn percent(&self) -> Option<f64> {
+        self.total
+            .filter(|&x| x > 0)
+            .map(|x| 100.0 * self.done as f64 / x as f64)
+    }
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new named phase of work, resetting the count. `total` is how many
+    /// items the phase will get through, if that's known up front.
+    pub fn phase(&self, name: &str, total: Option<u64>) {
+        *self.0.phase.lock().unwrap() = Some((name.to_string(), total));
+        self.0.done.store(0, Ordering::Relaxed);
+    }
+
+    /// Count `items` more as done; safe to call from several threads at once
+    pub fn advance(&self, items: u64) {
+        self.0.done.fetch_add(items, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> Option<Snapshot> {
+        let phase = self.0.phase.lock().unwrap().clone();
+        phase.map(|(phase, total)| Snapshot {
+            phase,
+            done: self.0.done.load(Ordering::Relaxed),
+            total,
+        })
+    }
+}